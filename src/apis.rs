@@ -1,7 +1,9 @@
 use rocket::{Rocket, Build};
 
 pub mod v1;
+pub mod v2;
+pub mod graphql;
 
 pub fn init_api_routes(server: Rocket<Build>) -> Rocket<Build> {
-    v1::init_v1_api(server)
+    graphql::init_graphql_api(v2::init_v2_api(v1::init_v1_api(server)))
 }
\ No newline at end of file
@@ -0,0 +1,19 @@
+use rocket::{Rocket, Build, serde::json::Json, response::content::RawHtml};
+use async_graphql::http::GraphiQLSource;
+
+use crate::{types::AuthToken, request_handler::AuthorizationToken, graphql::{execute, API_PATH}};
+
+// Errors of the fields are in `errors` of the response, only a failed token guard is answered by the 401 catcher.
+#[post("/", data = "<request>")]
+pub async fn api(auth: AuthorizationToken<AuthToken>, request: Json<async_graphql::Request>) -> Json<async_graphql::Response> {
+    Json(execute(request.into_inner(), auth.0).await)
+}
+
+#[get("/")]
+pub fn api_graphiql() -> RawHtml<String> {
+    RawHtml(GraphiQLSource::build().endpoint(API_PATH).finish())
+}
+
+pub fn init_graphql_api(server: Rocket<Build>) -> Rocket<Build> {
+    server.mount(API_PATH, routes![api, api_graphiql])
+}
@@ -0,0 +1,130 @@
+use std::future::Future;
+use rocket::{response::status::Custom, http::Status, form};
+use tokio::task::JoinHandle;
+use utoipa::ToSchema;
+
+use crate::{
+    responder::Json,
+    i18n::Message,
+    request_handler::AuthorizationToken,
+    types::{AuthToken, APIResponseJSON, HTTPResponse, ErrorReturn, BatchData, BatchCollect},
+    apis::v1::{
+        get_user_info_short::fetch_user_info_short, get_available_score::get_available_score, get_lack::get_lack,
+        get_reward_and_punish::get_reward_and_punish
+    },
+    error::{ErrorCode, HTTPError},
+    utils
+};
+
+const API_PATH: &str = "/v1/batch";
+
+#[derive(Debug, FromFormField, Clone, Copy, PartialEq, ToSchema)]
+#[schema(rename_all = "camelCase")]
+pub enum BatchSection {
+    User,
+    AvailableScore,
+    Lack,
+    RewardAndPunish
+}
+
+impl BatchSection {
+    fn name(&self) -> &'static str {
+        match self {
+            BatchSection::User => "user",
+            BatchSection::AvailableScore => "availableScore",
+            BatchSection::Lack => "lack",
+            BatchSection::RewardAndPunish => "rewardAndPunish"
+        }
+    }
+}
+
+// Every section runs in its own task, so a panic of one scraper only fails its section.
+fn spawn<T, F>(wanted: bool, future: F) -> Option<JoinHandle<HTTPResponse<T>>>
+where
+    T: Send + 'static,
+    F: Future<Output = HTTPResponse<T>> + Send + 'static
+{
+    wanted.then(|| tokio::spawn(future))
+}
+
+// Splits the result of a section into the data and the error of the section.
+async fn collect<T>(section: BatchSection, task: Option<JoinHandle<HTTPResponse<T>>>, errors: &mut Vec<(BatchSection, ErrorReturn)>) -> Option<T> {
+    match task?.await {
+        Ok(Ok(data)) => Some(data),
+        Ok(Err(err)) => {
+            errors.push((section, err));
+            None
+        },
+        Err(_) => {
+            let at = format!("Section: {}", section.name());
+            errors.push((section, utils::error_message(API_PATH, Status::InternalServerError, ErrorCode::ServerError, HTTPError::ServerError.message(), Some(&at))));
+            None
+        }
+    }
+}
+
+// The sections are fetched concurrently with the same session, every section is fetched when `sections` is omitted.
+// It only fails when every requested section has failed.
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "batch",
+    tag = "User",
+    params(
+        ("sections" = Option<Vec<BatchSection>>, Query, description = "Sections to fetch, all of them when omitted")
+    ),
+    responses(
+        (status = 200, description = "Requested sections, with the error of every failed section", body = BatchData)
+    ),
+    security(("authToken" = []))
+)]
+#[get("/batch?<sections>")]
+pub async fn api(auth: AuthorizationToken<AuthToken>, sections: form::Result<'_, Vec<BatchSection>>) -> APIResponseJSON<BatchData> {
+    let token = auth.0;
+    let sections = match sections {
+        Ok(sections) => sections,
+        Err(_) => return Err(utils::error_message(API_PATH, Status::BadRequest, ErrorCode::InvalidArgument, Message::WrongArguments, Some("Argument: sections")))
+    };
+    let wanted = |section: BatchSection| sections.is_empty() || sections.contains(&section);
+
+    let user = spawn(wanted(BatchSection::User), {
+        let token = token.clone();
+        async move { fetch_user_info_short(API_PATH, &token).await }
+    });
+    let available_score = spawn(wanted(BatchSection::AvailableScore), {
+        let token = token.clone();
+        async move { get_available_score(API_PATH, &token).await }
+    });
+    let lack = spawn(wanted(BatchSection::Lack), {
+        let token = token.clone();
+        async move { get_lack(API_PATH, &token).await }
+    });
+    let reward_and_punish = spawn(wanted(BatchSection::RewardAndPunish), {
+        let token = token.clone();
+        async move { get_reward_and_punish(API_PATH, &token).await }
+    });
+
+    // The tasks are already running, so awaiting them in turn still fetches the sections concurrently.
+    let mut errors = Vec::new();
+    let user = collect(BatchSection::User, user, &mut errors).await;
+    let available_score = collect(BatchSection::AvailableScore, available_score, &mut errors).await;
+    let lack = collect(BatchSection::Lack, lack, &mut errors).await;
+    let reward_and_punish = collect(BatchSection::RewardAndPunish, reward_and_punish, &mut errors).await;
+
+    if user.is_none() && available_score.is_none() && lack.is_none() && reward_and_punish.is_none() && !errors.is_empty() {
+        return Err(errors.remove(0).1)
+    }
+
+    Ok(Custom(Status::Ok, Json(BatchData {
+        message: Message::GetBatch,
+        data: BatchCollect {
+            user,
+            availableScore: available_score,
+            lack,
+            rewardAndPunish: reward_and_punish,
+            errors: errors
+                .into_iter()
+                .map(|(section, err)| (section.name().to_owned(), err.1.into_inner()))
+                .collect()
+        }
+    })))
+}
@@ -0,0 +1,135 @@
+use rocket::{http::{Status, ContentType}, response::status::Custom};
+
+use crate::{
+    responder::Json,
+    i18n::Message,
+    request_handler::AuthorizationToken,
+    types::{AuthToken, APIResponseJSON, HTTPResponse, ErrorReturn, CalendarFeedData, CalendarFeedCollect},
+    calendar::{create_feed, revoke_feed, get_feed, get_feed_auth, update_feed_cache, create_schedule_calendar, create_lack_calendar},
+    apis::v1::{get_schedule::get_own_schedule, get_lack::{fetch_lack, to_typed_records}},
+    responder::FileResponse,
+    config::read_config,
+    error::{HTTPError, ErrorCode},
+    utils
+};
+
+const API_PATH: &str = "/v1/calendarFeed";
+const SCHEDULE_PATH: &str = "/v1/schedule.ics";
+const LACK_PATH: &str = "/v1/lack.ics";
+
+fn calendar_response(calendar: String) -> FileResponse {
+    FileResponse {
+        content_type: ContentType::Calendar,
+        file: calendar.into_bytes(),
+        file_name: None
+    }
+}
+
+fn feed_not_found(path: &str) -> ErrorReturn {
+    utils::error_message(path, Status::NotFound, ErrorCode::NotFound, Message::CalendarFeedNotFound, Some("Argument: token"))
+}
+
+// Calendar apps cannot send the authorization header, so the feeds are accessed by a long random token in url.
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "createCalendarFeed",
+    tag = "Calendar",
+    responses(
+        (status = 200, description = "Calendar feed urls", body = CalendarFeedData)
+    ),
+    security(("authToken" = []))
+)]
+#[post("/calendarFeed")]
+pub async fn api(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<CalendarFeedData> {
+    let token = create_feed(auth.0);
+
+    Ok(Custom(Status::Ok, Json(CalendarFeedData {
+        message: Message::CreateCalendarFeed,
+        data: CalendarFeedCollect {
+            scheduleUrl: format!("{}?token={}", SCHEDULE_PATH, token),
+            lackUrl: format!("{}?token={}", LACK_PATH, token),
+            token
+        }
+    })))
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "revokeCalendarFeed",
+    tag = "Calendar",
+    responses(
+        (status = 200, description = "Revoked calendar feed", body = CalendarFeedData)
+    ),
+    security(("authToken" = []))
+)]
+#[delete("/calendarFeed")]
+pub async fn api_delete(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<CalendarFeedData> {
+    if !revoke_feed(&auth.0) {
+        return Err(utils::error_message(API_PATH, Status::NotFound, ErrorCode::NotFound, Message::CalendarFeedNotFound, None))
+    }
+
+    Ok(Custom(Status::Ok, Json(CalendarFeedData {
+        message: Message::RevokeCalendarFeed,
+        data: CalendarFeedCollect {
+            token: String::new(),
+            scheduleUrl: String::new(),
+            lackUrl: String::new()
+        }
+    })))
+}
+
+// The last generated calendar is served when the session of the feed is expired or the school system is down.
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getScheduleCalendar",
+    tag = "Calendar",
+    responses(
+        (status = 200, description = "Schedule calendar", content_type = "text/calendar")
+    )
+)]
+#[get("/schedule.ics?<token>")]
+pub async fn api_schedule(token: &str) -> HTTPResponse<FileResponse> {
+    let feed = get_feed(token).ok_or_else(|| feed_not_found(SCHEDULE_PATH))?;
+
+    if let Some(auth) = get_feed_auth(&feed) {
+        if let Ok(schedule) = get_own_schedule(SCHEDULE_PATH, &auth).await {
+            let config = read_config();
+            let calendar = create_schedule_calendar(&feed.school_number, &schedule.schedule, &config.period_times, config.schedule_repeat_weeks);
+
+            update_feed_cache(token, Some(calendar.clone()), None);
+            return Ok(calendar_response(calendar))
+        }
+    }
+
+    match feed.schedule {
+        Some(calendar) => Ok(calendar_response(calendar)),
+        None => Err(utils::error_message(SCHEDULE_PATH, Status::Forbidden, HTTPError::SessionExpired.code(), HTTPError::SessionExpired.message(), None))
+    }
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getLackCalendar",
+    tag = "Calendar",
+    responses(
+        (status = 200, description = "Lack calendar", content_type = "text/calendar")
+    )
+)]
+#[get("/lack.ics?<token>")]
+pub async fn api_lack(token: &str) -> HTTPResponse<FileResponse> {
+    let feed = get_feed(token).ok_or_else(|| feed_not_found(LACK_PATH))?;
+
+    if let Some(auth) = get_feed_auth(&feed) {
+        if let Ok((lack, periods)) = fetch_lack(LACK_PATH, &auth).await {
+            let calendar = create_lack_calendar(&feed.school_number, &to_typed_records(&lack.record, &periods));
+
+            update_feed_cache(token, None, Some(calendar.clone()));
+            return Ok(calendar_response(calendar))
+        }
+    }
+
+    match feed.lack {
+        Some(calendar) => Ok(calendar_response(calendar)),
+        None => Err(utils::error_message(LACK_PATH, Status::Forbidden, HTTPError::SessionExpired.code(), HTTPError::SessionExpired.message(), None))
+    }
+}
\ No newline at end of file
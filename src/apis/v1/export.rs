@@ -0,0 +1,116 @@
+use rocket::http::Status;
+use rust_xlsxwriter::XlsxError;
+
+use crate::{
+    i18n::Message,
+    request_handler::AuthorizationToken,
+    types::{AuthToken, HTTPResponse, ErrorReturn},
+    apis::v1::{get_score::get_score, get_all_scores::get_all_scores, get_lack::fetch_lack, get_reward_and_punish::get_reward_and_punish},
+    export::{ExportFormat, ExportSheet, export, score_to_sheets, all_score_to_sheets, lack_to_sheets, reward_and_punish_to_sheets},
+    responder::FileResponse,
+    utils,
+    error::{ErrorCode, HTTPError}
+};
+
+const SCORE_PATH: &str = "/v1/exportScore";
+const ALL_SCORES_PATH: &str = "/v1/exportAllScores";
+const LACK_PATH: &str = "/v1/exportLack";
+const REWARD_AND_PUNISH_PATH: &str = "/v1/exportRewAndPun";
+
+fn export_response(sheets: &[ExportSheet], format: Option<ExportFormat>, name: &str) -> Result<FileResponse, XlsxError> {
+    let format = format.unwrap_or(ExportFormat::Csv);
+
+    // Only keep safe characters, so the file name never breaks the header.
+    let name = name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect::<String>();
+
+    Ok(FileResponse {
+        content_type: format.content_type(),
+        file: export(sheets, format)?,
+        file_name: Some(format!("{}.{}", name, format.extension()))
+    })
+}
+
+fn export_failed(api: &str) -> ErrorReturn {
+    utils::error_message(api, Status::InternalServerError, ErrorCode::RenderFailed, HTTPError::ServerError.message(), Some("Writing export"))
+}
+
+fn missing_arguments() -> ErrorReturn {
+    utils::error_message(SCORE_PATH, Status::BadRequest, ErrorCode::MissingArgument, Message::MissingArguments, Some("Arguments"))
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "exportScore",
+    tag = "Export",
+    responses(
+        (status = 200, description = "Score of the exam", content_type = ["text/csv", "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"])
+    ),
+    security(("authToken" = []))
+)]
+#[get("/exportScore?<year>&<term>&<testID>&<format>")]
+#[allow(non_snake_case)]
+pub async fn api_score(auth: AuthorizationToken<AuthToken>, year: Option<String>, term: Option<String>, testID: Option<String>, format: Option<ExportFormat>) -> HTTPResponse<FileResponse> {
+    let token = auth.0;
+    let (year, term, test_id) = match (year, term, testID) {
+        (Some(year), Some(term), Some(test_id)) => (year, term, test_id),
+        _ => return Err(missing_arguments())
+    };
+
+    let data = get_score(SCORE_PATH, &token, &year, &term, &test_id).await?;
+
+    export_response(&score_to_sheets(&data), format, &format!("score-{}-{}-{}", year, term, test_id)).map_err(|_| export_failed(SCORE_PATH))
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "exportAllScores",
+    tag = "Export",
+    responses(
+        (status = 200, description = "Scores of every semester and exam", content_type = ["text/csv", "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"])
+    ),
+    security(("authToken" = []))
+)]
+#[get("/exportAllScores?<format>")]
+pub async fn api_all_scores(auth: AuthorizationToken<AuthToken>, format: Option<ExportFormat>) -> HTTPResponse<FileResponse> {
+    let token = auth.0;
+    let data = get_all_scores(ALL_SCORES_PATH, &token).await?;
+
+    export_response(&all_score_to_sheets(&data), format, &format!("all-scores-{}", token.user_data.schoolNumber)).map_err(|_| export_failed(ALL_SCORES_PATH))
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "exportLack",
+    tag = "Export",
+    responses(
+        (status = 200, description = "Lack records", content_type = ["text/csv", "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"])
+    ),
+    security(("authToken" = []))
+)]
+#[get("/exportLack?<format>")]
+pub async fn api_lack(auth: AuthorizationToken<AuthToken>, format: Option<ExportFormat>) -> HTTPResponse<FileResponse> {
+    let token = auth.0;
+    let (data, periods) = fetch_lack(LACK_PATH, &token).await?;
+
+    export_response(&lack_to_sheets(&data, &periods), format, &format!("lack-{}", token.user_data.schoolNumber)).map_err(|_| export_failed(LACK_PATH))
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "exportRewAndPun",
+    tag = "Export",
+    responses(
+        (status = 200, description = "Reward and punish records", content_type = ["text/csv", "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"])
+    ),
+    security(("authToken" = []))
+)]
+#[get("/exportRewAndPun?<format>")]
+pub async fn api_reward_and_punish(auth: AuthorizationToken<AuthToken>, format: Option<ExportFormat>) -> HTTPResponse<FileResponse> {
+    let token = auth.0;
+    let data = get_reward_and_punish(REWARD_AND_PUNISH_PATH, &token).await?;
+
+    export_response(&reward_and_punish_to_sheets(&data), format, &format!("reward-and-punish-{}", token.user_data.schoolNumber)).map_err(|_| export_failed(REWARD_AND_PUNISH_PATH))
+}
@@ -0,0 +1,221 @@
+use rocket::{response::status::Custom, http::Status};
+
+use crate::{
+    responder::Json,
+    i18n::Message,
+    request_handler::AuthorizationToken,
+    types::{AuthToken, APIResponseJSON, HTTPResponse, AllScoreData, AllScoreTestCollect, AllScoreNormalData, AllScoreNormalDataValue, AllScoreTestData, AllScoreTestDataValue, AllScoreTestDataInfo},
+    utils::{combine_page_path, create_auth_header, http_get_html_err_handle, generate_parse_error},
+    http::APIPaths
+};
+
+const API_PATH: &str = "/v1/getAllScores";
+
+// A chart of the page, the series are the subjects and every category is a point of them.
+struct Chart {
+    categories: Vec<String>,
+    series: Vec<(String, Vec<Option<f32>>)>
+}
+
+// Find the array after `key:`, which may contain nested arrays, objects and quoted strings.
+fn find_array<'a>(source: &'a str, key: &str) -> Option<(&'a str, usize)> {
+    let position = source.find(&format!("{}:", key))?;
+    let start = position + source[position..].find('[')?;
+
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+
+    for (i, c) in source[start..].char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue
+        }
+
+        match c {
+            '\'' | '"' => quote = Some(c),
+            '[' | '{' => depth += 1,
+            ']' | '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((&source[start + 1..start + i], start + i + 1))
+                }
+            },
+            _ => {}
+        }
+    }
+
+    None
+}
+
+// Split the items of an array on the commas outside of quotes and brackets.
+fn split_items(source: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut last = 0;
+
+    for (i, c) in source.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {},
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                items.push(source[last..i].trim());
+                last = i + 1;
+            },
+            _ => {}
+        }
+    }
+
+    let rest = source[last..].trim();
+    if !rest.is_empty() {
+        items.push(rest);
+    }
+
+    items
+}
+
+fn unquote(source: &str) -> String {
+    source.trim().trim_matches(['\'', '"']).trim().to_owned()
+}
+
+fn find_name(source: &str) -> Option<String> {
+    let position = source.find("name:")?;
+    let rest = source[position + 5..].trim_start();
+    let quote = rest.chars().next().filter(|c| *c == '\'' || *c == '"')?;
+    let end = rest[1..].find(quote)?;
+
+    Some(rest[1..end + 1].trim().to_owned())
+}
+
+fn read_charts(script: &str) -> Vec<Chart> {
+    let mut charts = Vec::new();
+    let mut rest = script;
+
+    while let Some((categories, end)) = find_array(rest, "categories") {
+        rest = &rest[end..];
+
+        let (series, end) = match find_array(rest, "series") {
+            Some(series) => series,
+            None => break
+        };
+        rest = &rest[end..];
+
+        let series = split_items(series)
+            .into_iter()
+            .filter_map(|item| {
+                let name = find_name(item)?;
+                let (data, _) = find_array(item, "data")?;
+                let values = split_items(data).into_iter().map(|v| v.parse::<f32>().ok()).collect::<Vec<_>>();
+
+                Some((name, values))
+            })
+            .collect::<Vec<_>>();
+
+        charts.push(Chart {
+            categories: split_items(categories).into_iter().map(unquote).collect(),
+            series
+        });
+    }
+
+    charts
+}
+
+// The exam names hold the year, term and times, like "110學年第1學期第2次段考".
+fn parse_test_info(name: &str) -> Option<AllScoreTestDataInfo> {
+    let numbers = name
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+
+    match numbers[..] {
+        [year, term, test, ..] => Some(AllScoreTestDataInfo {
+            name: name.to_owned(),
+            year: year.parse().ok()?,
+            term: term.parse().ok()?,
+            test: test.parse().ok()?
+        }),
+        _ => None
+    }
+}
+
+// Charts whose categories are exams go to `dataTest`, the others (like the semester scores) to `dataNormal`.
+pub fn parse_all_scores(html: &str) -> Option<AllScoreTestCollect> {
+    let charts = read_charts(html);
+    if charts.is_empty() {
+        return None
+    }
+
+    let mut collect = AllScoreTestCollect {
+        dataNormal: Vec::new(),
+        dataTest: Vec::new()
+    };
+
+    for chart in charts {
+        let tests = chart.categories.iter().map(|c| parse_test_info(c)).collect::<Option<Vec<_>>>();
+
+        for (name, data) in chart.series {
+            match &tests {
+                Some(tests) => collect.dataTest.push(AllScoreTestData {
+                    name,
+                    values: tests
+                        .iter()
+                        .zip(data)
+                        .filter_map(|(info, value)| Some(AllScoreTestDataValue {
+                            name: info.clone(),
+                            value: value?
+                        }))
+                        .collect()
+                }),
+                None => collect.dataNormal.push(AllScoreNormalData {
+                    name,
+                    values: chart.categories
+                        .iter()
+                        .zip(data)
+                        .filter_map(|(category, value)| Some(AllScoreNormalDataValue {
+                            name: category.clone(),
+                            value: value?
+                        }))
+                        .collect()
+                })
+            }
+        }
+    }
+
+    Some(collect)
+}
+
+pub async fn get_all_scores(api: &str, token: &AuthToken) -> HTTPResponse<AllScoreTestCollect> {
+    let url = combine_page_path(&token.host, APIPaths::AllScores);
+    let data = http_get_html_err_handle(api, &url, Some(create_auth_header(&token.cookie))).await?;
+
+    parse_all_scores(&data.html.html()).ok_or_else(|| generate_parse_error(api, "Score charts"))
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getAllScores",
+    tag = "User",
+    responses(
+        (status = 200, description = "Scores of every semester and exam", body = AllScoreData)
+    ),
+    security(("authToken" = []))
+)]
+#[get("/getAllScores")]
+pub async fn api(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<AllScoreData> {
+    let data = get_all_scores(API_PATH, &auth.0).await?;
+
+    Ok(Custom(Status::Ok, Json(AllScoreData {
+        message: Message::GetAllScores,
+        data
+    })))
+}
@@ -1,13 +1,16 @@
 use lazy_static::lazy_static;
-use rocket::{response::status::Custom, serde::json::Json, http::Status};
 use scraper::Selector;
 use url::Url;
 
 use crate::{
-    types::{APIResponseJSON, AvailableScoreData, AuthToken, AvailableScoreValue},
+    i18n::Message,
+    types::{AvailableScoreData, AuthToken, AvailableScoreValue, HTTPResponse},
     request_handler::AuthorizationToken,
+    responder::ETagResponse,
+    etag::IfNoneMatch,
     http::APIPaths,
-    utils::{combine_page_path, create_auth_header, convert_string_to_u32, generate_session_expire_error, http_get_html_err_handle, find_string_in_url}
+    date::academic_year_to_gregorian,
+    utils::{combine_page_path, create_auth_header, convert_string_to_u32, generate_session_expire_error, generate_parse_error, http_get_html_err_handle, find_string_in_url}
 };
 
 lazy_static! {
@@ -16,46 +19,68 @@ lazy_static! {
 
 const API_PATH: &str = "/v1/getAvailableScore";
 
-#[get("/getAvailableScore")]
-pub async fn api(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<AvailableScoreData> {
-    let token = auth.0;
-
+pub async fn get_available_score(api: &str, token: &AuthToken) -> HTTPResponse<Vec<AvailableScoreValue>> {
     let page = combine_page_path(&token.host, APIPaths::ScoreList);
 
-    let respond = http_get_html_err_handle(API_PATH, &page, Some(create_auth_header(&token.cookie))).await?;
+    let respond = http_get_html_err_handle(api, &page, Some(create_auth_header(&token.cookie))).await?;
 
     if !respond.code.is_success() {
-        return Err(generate_session_expire_error(API_PATH))
+        return Err(generate_session_expire_error(api))
     }
 
-    let mut selected = respond.html.select(&LIST_SELECTOR).collect::<Vec<_>>();
+    let selected = respond.html.select(&LIST_SELECTOR).collect::<Vec<_>>();
     let mut data: Vec<AvailableScoreValue> = Vec::new();
 
-    selected.remove(0);
-    selected.remove(0);
-
-    for ele in selected {
-        let parse_url_string = ele.value().attr("value").unwrap().to_string();
-        let parse_url = Url::parse(&format!("http://example.com/{}", parse_url_string)).unwrap();
+    // The first two options are placeholders of the select.
+    for ele in selected.into_iter().skip(2) {
+        let parse_url = match ele.value().attr("value").and_then(|value| Url::parse(&format!("http://example.com/{}", value)).ok()) {
+            Some(url) => url,
+            None => return Err(generate_parse_error(api, "Exam list"))
+        };
         let search_params = parse_url.query_pairs();
 
-        println!("{}: {:?}", parse_url_string, parse_url);
-        
         let inner = ele.inner_html();
         let test_id = find_string_in_url(&search_params, "number");
 
+        let times = match test_id.get(3..4) {
+            Some(times) => convert_string_to_u32(times) as u8,
+            None => return Err(generate_parse_error(api, "Exam list"))
+        };
+
+        let year = convert_string_to_u32(&find_string_in_url(&search_params, "thisyear")) as u8;
+
         data.push(AvailableScoreValue {
             name: inner.clone(),
-            year: convert_string_to_u32(&find_string_in_url(&search_params, "thisyear")) as u8,
+            year,
+            gregorianYear: academic_year_to_gregorian(year as u16),
             term: convert_string_to_u32(&find_string_in_url(&search_params, "thisterm")) as u8,
             testID: test_id.clone(),
-            times: convert_string_to_u32(&test_id[3..4]) as u8,
+            times,
             r#type: if inner.contains("平時成績") { 2 } else { 1 }
         });
     }
 
-    Ok(Custom(Status::Ok, Json(AvailableScoreData {
-        message: "Get available score data successful".to_owned(),
+    Ok(data)
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getAvailableScore",
+    tag = "User",
+    responses(
+        (status = 200, description = "Opened exams", body = AvailableScoreData),
+        (status = 304, description = "Not modified since the `ETag` in `If-None-Match`")
+    ),
+    security(("authToken" = []))
+)]
+#[get("/getAvailableScore")]
+pub async fn api(auth: AuthorizationToken<AuthToken>, if_none_match: IfNoneMatch) -> HTTPResponse<ETagResponse> {
+    let token = auth.0;
+
+    let data = get_available_score(API_PATH, &token).await?;
+
+    Ok(if_none_match.respond(&token, API_PATH, &AvailableScoreData {
+        message: Message::GetAvailableScore,
         data
-    })))
+    }))
 }
\ No newline at end of file
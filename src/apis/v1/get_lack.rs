@@ -1,12 +1,15 @@
 use lazy_static::lazy_static;
-use rocket::{response::status::Custom, http::Status, serde::json::Json};
 use scraper::Selector;
 
 use crate::{
+    i18n::Message,
     request_handler::AuthorizationToken,
-    types::{AuthToken, APIResponseJSON, LackData, LackStatusValue, LackRecordValue, LackCollect, LackStatus},
-    utils::{combine_page_path, http_get_html_err_handle, create_auth_header, html_to_text, convert_string_to_u32},
-    http::APIPaths
+    responder::ETagResponse,
+    etag::IfNoneMatch,
+    types::{AuthToken, LackData, LackStatusValue, LackRecordValue, LackCollect, LackStatus, HTTPResponse, LackDataResponse, LackTypedData, LackTypedCollect, LackTypedRecord, Weekday, LackPeriod, AbsenceKind},
+    utils::{combine_page_path, http_get_html_err_handle, create_auth_header, html_to_text, convert_string_to_u32, check_str_is_num, generate_parse_error},
+    http::APIPaths,
+    date::parse_roc_date
 };
 
 lazy_static! {
@@ -15,29 +18,27 @@ lazy_static! {
     static ref TD_SELECT: Selector = Selector::parse("td").unwrap();
     static ref TABLE_SELECT: Selector = Selector::parse("table.padding2.spacing0").unwrap();
     static ref RECORD_SELECT: Selector = Selector::parse("tr:not(.td_03.si_12.le_05.top.center)").unwrap();
+    static ref HEADER_SELECT: Selector = Selector::parse("tr.td_03.si_12.le_05.top.center").unwrap();
 }
 
 const API_PATH: &str = "/v1/getLack";
 
-#[get("/getLack")]
-pub async fn api(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<LackData> {
-    let token = auth.0;
-
+// Returns the lack data with the period label of every record column.
+pub async fn fetch_lack(api: &str, token: &AuthToken) -> HTTPResponse<(LackCollect, Vec<String>)> {
     let page = combine_page_path(&token.host, APIPaths::Lack);
-    let data = http_get_html_err_handle(API_PATH, &page, Some(create_auth_header(&token.cookie))).await?;
+    let data = http_get_html_err_handle(api, &page, Some(create_auth_header(&token.cookie))).await?;
 
-    let summary = data.html
-        .select(&SUMMARIZE_TABLE_SELECT)
-        .next()
-        .unwrap()
+    let (summary_table, record_table) = match (data.html.select(&SUMMARIZE_TABLE_SELECT).next(), data.html.select(&TABLE_SELECT).next()) {
+        (Some(summary), Some(record)) => (summary, record),
+        _ => return Err(generate_parse_error(api, "Lack table"))
+    };
+
+    let summary = summary_table
         .select(&SUMMARIZE_RECORD_SELECT)
         .filter(|e| e
             .select(&TD_SELECT)
             .next()
-            .unwrap()
-            .value()
-            .attr("colspan")
-            .is_none())
+            .is_some_and(|td| td.value().attr("colspan").is_none()))
         .map(|ele| ele
             .select(&TD_SELECT)
             .map(|e| html_to_text(e))
@@ -46,6 +47,10 @@ pub async fn api(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<LackDat
     let mut summary_term_up_list: Vec<LackStatusValue> = Vec::new();
     let mut summary_term_down_list: Vec<LackStatusValue> = Vec::new();
 
+    if summary.len() < 4 || summary[..4].iter().any(|row| row.len() < 18) {
+        return Err(generate_parse_error(api, "Lack summary"))
+    }
+
     for i in 0..18 {
         summary_term_up_list.push(LackStatusValue {
             name: summary[0][i].clone(),
@@ -58,23 +63,23 @@ pub async fn api(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<LackDat
         });
     }
 
-    let record = data.html
-        .select(&TABLE_SELECT)
-        .next()
-        .unwrap()
+    let record = record_table
         .select(&RECORD_SELECT)
         .map(|ele| {
             let mut i = 0;
 
             let mut datas: Vec<Option<String>> = Vec::new();
-            let mut data: LackRecordValue = LackRecordValue { data: Vec::new(), date: String::new(), week: String::new() };
+            let mut data: LackRecordValue = LackRecordValue { data: Vec::new(), date: String::new(), isoDate: None, week: String::new() };
             let selt = ele.select(&TD_SELECT).collect::<Vec<_>>();
             for e in selt {
                 i += 1;
                 let text = html_to_text(e);
                 match i {
                     1 => data.week = text,
-                    2 => data.date = text,
+                    2 => {
+                        data.isoDate = parse_roc_date(&text);
+                        data.date = text;
+                    },
                     _ => if i != 3 {
                         datas.push(if text != "" { Some(text) } else { None });
                     }
@@ -87,14 +92,155 @@ pub async fn api(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<LackDat
         })
         .collect::<Vec<_>>();
 
-    Ok(Custom(Status::Ok, Json(LackData {
-        message: "Get lack successful".to_owned(),
-        data: LackCollect {
-            record,
-            total: LackStatus {
-                termUp: summary_term_up_list,
-                termDown: summary_term_down_list
+    let periods = match record_table.select(&HEADER_SELECT).next() {
+        Some(header) => header
+            .select(&TD_SELECT)
+            .skip(3)
+            .map(|e| html_to_text(e).replace(" ", "").replace("\n", ""))
+            .collect::<Vec<_>>(),
+        None => Vec::new()
+    };
+
+    Ok((LackCollect {
+        record,
+        total: LackStatus {
+            termUp: summary_term_up_list,
+            termDown: summary_term_down_list
+        }
+    }, periods))
+}
+
+pub async fn get_lack(api: &str, token: &AuthToken) -> HTTPResponse<LackCollect> {
+    Ok(fetch_lack(api, token).await?.0)
+}
+
+fn decode_weekday(week: &str) -> Weekday {
+    match week.trim().chars().last() {
+        Some('一') => Weekday::Monday,
+        Some('二') => Weekday::Tuesday,
+        Some('三') => Weekday::Wednesday,
+        Some('四') => Weekday::Thursday,
+        Some('五') => Weekday::Friday,
+        Some('六') => Weekday::Saturday,
+        Some('日') | Some('天') => Weekday::Sunday,
+        _ => Weekday::Unknown
+    }
+}
+
+const CHINESE_DIGITS: [char; 9] = ['一', '二', '三', '四', '五', '六', '七', '八', '九'];
+
+fn is_numeral(c: &char) -> bool {
+    c.is_ascii_digit() || *c == '十' || CHINESE_DIGITS.contains(c)
+}
+
+// "十一" is 11 and "二十" is 20, so every char of the numeral is read instead of the first match.
+fn parse_chinese_numeral(numeral: &str) -> Option<u8> {
+    let (mut total, mut digit) = (0u8, 0u8);
+
+    for c in numeral.chars() {
+        if c == '十' {
+            total = total.checked_add(digit.max(1) * 10)?;
+            digit = 0;
+        } else {
+            digit = CHINESE_DIGITS.iter().position(|d| *d == c)? as u8 + 1;
+        }
+    }
+
+    total.checked_add(digit)
+}
+
+pub fn decode_period(label: &str) -> LackPeriod {
+    if label.contains("早") {
+        return LackPeriod::EarlyStudy
+    }
+    if label.contains("升旗") {
+        return LackPeriod::FlagRaising
+    }
+    if label.contains("午") {
+        return LackPeriod::NoonBreak
+    }
+    if label.contains("掃") {
+        return LackPeriod::Cleaning
+    }
+
+    // The first numeral of the label, such as "3", "第十一節" or "八".
+    let numeral = label.chars().skip_while(|c| !is_numeral(c)).take_while(is_numeral).collect::<String>();
+    let number = if check_str_is_num(&numeral) { numeral.parse::<u8>().ok() } else { parse_chinese_numeral(&numeral) };
+
+    match number {
+        Some(number) if number > 0 => LackPeriod::Class(number),
+        _ => LackPeriod::Other
+    }
+}
+
+fn decode_absence(code: &str) -> AbsenceKind {
+    match code.chars().next() {
+        Some('曠') => AbsenceKind::Absent,
+        Some('遲') => AbsenceKind::Late,
+        Some('早') | Some('退') => AbsenceKind::EarlyLeave,
+        Some('事') => AbsenceKind::PersonalLeave,
+        Some('病') => AbsenceKind::SickLeave,
+        Some('公') => AbsenceKind::OfficialLeave,
+        Some('喪') => AbsenceKind::BereavementLeave,
+        Some('生') => AbsenceKind::MenstrualLeave,
+        _ => AbsenceKind::Other
+    }
+}
+
+pub fn to_typed_records(records: &[LackRecordValue], periods: &[String]) -> Vec<LackTypedRecord> {
+    let mut typed: Vec<LackTypedRecord> = Vec::new();
+
+    for record in records {
+        for (index, cell) in record.data.iter().enumerate() {
+            if let Some(code) = cell {
+                let label = periods.get(index).cloned().unwrap_or_else(|| (index + 1).to_string());
+
+                typed.push(LackTypedRecord {
+                    date: record.date.clone(),
+                    isoDate: record.isoDate.clone(),
+                    weekday: decode_weekday(&record.week),
+                    period: decode_period(&label),
+                    periodLabel: label,
+                    kind: decode_absence(code),
+                    code: code.clone()
+                });
             }
         }
-    })))
+    }
+
+    typed
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getLack",
+    tag = "User",
+    responses(
+        (status = 200, description = "Lack records, typed when `typed` is set", body = LackDataResponse),
+        (status = 304, description = "Not modified since the `ETag` in `If-None-Match`")
+    ),
+    security(("authToken" = []))
+)]
+#[get("/getLack?<typed>")]
+pub async fn api(auth: AuthorizationToken<AuthToken>, typed: Option<bool>, if_none_match: IfNoneMatch) -> HTTPResponse<ETagResponse> {
+    let token = auth.0;
+    let typed = typed.unwrap_or(false);
+    let (data, periods) = fetch_lack(API_PATH, &token).await?;
+
+    let response = if typed {
+        LackDataResponse::Typed(LackTypedData {
+            message: Message::GetLack,
+            data: LackTypedCollect {
+                record: to_typed_records(&data.record, &periods),
+                total: data.total
+            }
+        })
+    } else {
+        LackDataResponse::Raw(LackData {
+            message: Message::GetLack,
+            data
+        })
+    };
+
+    Ok(if_none_match.respond(&token, &format!("{}?typed={}", API_PATH, typed), &response))
 }
\ No newline at end of file
@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+use rocket::{response::status::Custom, http::Status};
+
+use crate::{
+    responder::Json,
+    i18n::Message,
+    request_handler::AuthorizationToken,
+    types::{AuthToken, APIResponseJSON, LackAnalyticsData, LackAnalyticsCollect, LackCategoryCount, LackPeriodCount, LackMonthCount, LackRuleStatus, LackRuleLevel, LackRuleConfig, LackStatusValue, LackRecordValue},
+    apis::v1::get_lack::{fetch_lack, to_typed_records},
+    config::read_config,
+    date::parse_roc_month
+};
+
+const API_PATH: &str = "/v1/getLackAnalytics";
+
+fn get_month(date: &str) -> String {
+    date
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|s| !s.is_empty())
+        .take(2)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// Periods are counted by the header label of their column, in the order of the header.
+pub fn count_records(records: &[LackRecordValue], periods: &[String]) -> (Vec<LackCategoryCount>, Vec<LackPeriodCount>, Vec<LackMonthCount>) {
+    let mut categories: BTreeMap<String, u16> = BTreeMap::new();
+    let mut period_counts: Vec<LackPeriodCount> = Vec::new();
+    let mut months: BTreeMap<String, u16> = BTreeMap::new();
+
+    for record in to_typed_records(records, periods) {
+        *categories.entry(record.code).or_default() += 1;
+        *months.entry(get_month(&record.date)).or_default() += 1;
+
+        match period_counts.iter_mut().find(|p| p.label == record.periodLabel) {
+            Some(count) => count.count += 1,
+            None => period_counts.push(LackPeriodCount { period: record.period, label: record.periodLabel, count: 1 })
+        }
+    }
+
+    period_counts.sort_by_key(|p| periods.iter().position(|label| *label == p.label).unwrap_or(usize::MAX));
+
+    (
+        categories.into_iter().map(|(name, count)| LackCategoryCount { name, count }).collect(),
+        period_counts,
+        months.into_iter().map(|(month, count)| LackMonthCount { isoMonth: parse_roc_month(&month), month, count }).collect()
+    )
+}
+
+fn check_rules(rules: &[LackRuleConfig], term: &str, values: &[LackStatusValue]) -> Vec<LackRuleStatus> {
+    rules
+        .iter()
+        .map(|rule| {
+            let value = values
+                .iter()
+                .filter(|v| v.name.contains(&rule.name))
+                .map(|v| v.value)
+                .sum::<u16>();
+            let level = if value >= rule.limit {
+                LackRuleLevel::Exceeded
+            } else if value >= rule.warning {
+                LackRuleLevel::Warning
+            } else {
+                LackRuleLevel::Normal
+            };
+
+            LackRuleStatus {
+                name: rule.name.clone(),
+                term: term.to_owned(),
+                value,
+                warning: rule.warning,
+                limit: rule.limit,
+                remain: rule.limit.saturating_sub(value),
+                level
+            }
+        })
+        .collect()
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getLackAnalytics",
+    tag = "Analytics",
+    responses(
+        (status = 200, description = "Lack statistics", body = LackAnalyticsData)
+    ),
+    security(("authToken" = []))
+)]
+#[get("/getLackAnalytics")]
+pub async fn api(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<LackAnalyticsData> {
+    let token = auth.0;
+
+    let (data, periods) = fetch_lack(API_PATH, &token).await?;
+    let rules = read_config().lack_rules;
+
+    let (categories, periods, months) = count_records(&data.record, &periods);
+    let mut rule_status = check_rules(&rules, "up", &data.total.termUp);
+    rule_status.extend(check_rules(&rules, "down", &data.total.termDown));
+
+    Ok(Custom(Status::Ok, Json(LackAnalyticsData {
+        message: Message::GetLackAnalytics,
+        data: LackAnalyticsCollect {
+            categories,
+            periods,
+            months,
+            rules: rule_status
+        }
+    })))
+}
\ No newline at end of file
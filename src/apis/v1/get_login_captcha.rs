@@ -10,20 +10,33 @@ use crate::{
 
 const API_PATH: &str = "/v1/getLoginCaptcha";
 
-#[get("/getLoginCaptcha")]
-pub async fn api(auth: AuthorizationToken<LoginInfoAuthToken>) -> HTTPResponse<FileResponse> {
-    let token = auth.0;
+pub async fn get_login_captcha(api: &str, token: &LoginInfoAuthToken) -> HTTPResponse<Vec<u8>> {
     let page = utils::combine_page_path(&token.host, APIPaths::LoginCaptcha);
 
-    let captcha: Vec<u8> = http_get_err_handle(API_PATH, &page, Some(create_auth_header(&token.cookie))).await?
+    Ok(http_get_err_handle(api, &page, Some(create_auth_header(&token.cookie))).await?
         .bytes()
         .await
         .unwrap()
         .into_iter()
-        .collect();
+        .collect())
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getLoginCaptcha",
+    tag = "Login",
+    responses(
+        (status = 200, description = "Captcha image", content_type = "image/gif")
+    ),
+    security(("loginInfoToken" = []))
+)]
+#[get("/getLoginCaptcha")]
+pub async fn api(auth: AuthorizationToken<LoginInfoAuthToken>) -> HTTPResponse<FileResponse> {
+    let token = auth.0;
 
     Ok(FileResponse {
         content_type: ContentType::GIF,
-        file: captcha
+        file: get_login_captcha(API_PATH, &token).await?,
+        file_name: None
     })
 }
\ No newline at end of file
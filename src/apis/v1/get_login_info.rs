@@ -1,13 +1,15 @@
 use lazy_static::lazy_static;
-use rocket::{serde::json::Json, http::Status, response::status::Custom};
+use rocket::{http::Status, response::status::Custom};
 use scraper::Selector;
 use reqwest::{header::SET_COOKIE, StatusCode};
 
 use crate::{
-    types::{LoginInfo, APIResponseJSON, LoginInfoAuthToken, ErrorReturn},
+    responder::Json,
+    i18n::Message,
+    types::{LoginInfo, APIResponseJSON, LoginInfoAuthToken, ErrorReturn, HTTPResponse},
     config::read_config,
     utils::{get_timestamp, self, get_time_after},
-    error::HTTPError,
+    error::{HTTPError, ErrorCode},
     http::{http_get_html, HTTPErrorReturn},
     secure::sign_jwt
 };
@@ -20,39 +22,33 @@ lazy_static! {
 }
 
 const API_PATH: &str = "/v1/getLoginInfo";
-fn error_message(code: Status, message: &str, at: &str) -> ErrorReturn {
-    utils::error_message(API_PATH, code, message, Some(at))
+fn error_message(api: &str, status: Status, code: ErrorCode, message: Message, at: &str) -> ErrorReturn {
+    utils::error_message(api, status, code, message, Some(at))
 }
 
-#[get("/getLoginInfo?<host>")]
-pub async fn api(host: Option<&str>) -> APIResponseJSON<LoginInfo> {
-    let host = match host {
-        Some(x) => x,
-        None => return Err(error_message(Status::BadRequest, "Wrong arguments", "Argument: host"))
-    };
-
+pub async fn get_login_info(api: &str, host: &str) -> HTTPResponse<LoginInfo> {
     let hst = match url::Url::parse(host) {
         Ok(r) => {
             let hos = r.host().unwrap();
             format!("{}://{}/online/", r.scheme(), hos)
         },
-        Err(_) => return Err(error_message(Status::InternalServerError, HTTPError::ServerError.message(), "Parsing host url"))
+        Err(_) => return Err(error_message(api, Status::InternalServerError, ErrorCode::InvalidHost, HTTPError::ServerError.message(), "Parsing host url"))
     };
 
     let respond = match http_get_html(&hst, None).await {
         Ok(v) => v,
         Err(err) => return Err(match err {
-            HTTPErrorReturn::RequestError(_) => error_message(Status::ServiceUnavailable, HTTPError::RemoteServiceUnavailable.message(), "Remote server"),
+            HTTPErrorReturn::RequestError(_) => error_message(api, Status::ServiceUnavailable, HTTPError::RemoteServiceUnavailable.code(), HTTPError::RemoteServiceUnavailable.message(), "Remote server"),
             HTTPErrorReturn::StatusCodeError(code) => match code {
-                StatusCode::NOT_FOUND => error_message(Status::BadRequest, HTTPError::NotAValidHost.message(), "Argument: host"),
-                _ => error_message(Status::BadGateway, HTTPError::ServerError.message(), "Return status code")
+                StatusCode::NOT_FOUND => error_message(api, Status::BadRequest, HTTPError::NotAValidHost.code(), HTTPError::NotAValidHost.message(), "Argument: host"),
+                _ => error_message(api, Status::BadGateway, ErrorCode::UpstreamError, HTTPError::ServerError.message(), "Return status code")
             }
         })
     };
 
     let cookie = match respond.header.get(SET_COOKIE) {
         Some(cookie) => utils::get_asp_cookie(cookie.to_str().unwrap()),
-        None => return Err(error_message(Status::ServiceUnavailable, HTTPError::RemoteServiceUnavailable.message(), "Remote server"))
+        None => return Err(error_message(api, Status::ServiceUnavailable, ErrorCode::ParseFailed, HTTPError::RemoteServiceUnavailable.message(), "Remote server"))
     };
 
     let r = match respond.html.select(&CHECK_SELECTOR).next() {
@@ -60,11 +56,11 @@ pub async fn api(host: Option<&str>) -> APIResponseJSON<LoginInfo> {
             let v = ele.value().attr("content").unwrap();
             String::from(v).eq(&"欣河資訊") 
         },
-        None => return Err(error_message(Status::BadRequest, HTTPError::NotAValidHost.message(), "Argument: host"))
+        None => return Err(error_message(api, Status::BadRequest, HTTPError::NotAValidHost.code(), HTTPError::NotAValidHost.message(), "Argument: host"))
     };
     
     if !r {
-        return Err(error_message(Status::BadRequest, HTTPError::NotAValidHost.message(), "Argument: host"));
+        return Err(error_message(api, Status::BadRequest, HTTPError::NotAValidHost.code(), HTTPError::NotAValidHost.message(), "Argument: host"));
     }
 
     let auth_code = respond.html.select(&VERIFY_CODE_SELECTOR).next().unwrap().value().attr("value").unwrap().to_string();
@@ -83,8 +79,26 @@ pub async fn api(host: Option<&str>) -> APIResponseJSON<LoginInfo> {
         exp: get_time_after(*EXPIRED_TIME)
     }).unwrap();
 
-    Ok(Custom(Status::Ok, Json(LoginInfo {
+    Ok(LoginInfo {
         authToken: token,
         need_captcha: is_captcha_needed
-    })))
-}
\ No newline at end of file
+    })
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getLoginInfo",
+    tag = "Login",
+    responses(
+        (status = 200, description = "Login token and whether a captcha is needed", body = LoginInfo)
+    )
+)]
+#[get("/getLoginInfo?<host>")]
+pub async fn api(host: Option<&str>) -> APIResponseJSON<LoginInfo> {
+    let host = match host {
+        Some(x) => x,
+        None => return Err(error_message(API_PATH, Status::BadRequest, ErrorCode::InvalidArgument, Message::WrongArguments, "Argument: host"))
+    };
+
+    Ok(Custom(Status::Ok, Json(get_login_info(API_PATH, host).await?)))
+}
@@ -0,0 +1,118 @@
+use rocket::{response::status::Custom, http::Status};
+
+use crate::{
+    responder::Json,
+    i18n::Message,
+    request_handler::AuthorizationToken,
+    types::{AuthToken, APIResponseJSON, MeritBalanceData, MeritBalanceCollect, MeritRuleConfig, RewardAndPunishStatus, RewardAndPunishDetailValue},
+    apis::v1::get_reward_and_punish::get_reward_and_punish,
+    config::read_config
+};
+
+const API_PATH: &str = "/v1/getMeritBalance";
+
+// Weight of the type in the smallest type of its list, e.g. 大過 is 9 警告 when ratio is 3.
+fn type_weight(types: &[String], name: &str, ratio: u16) -> Option<u32> {
+    types
+        .iter()
+        .rposition(|t| name.contains(t.as_str()))
+        .map(|i| (ratio as u32).pow(i as u32))
+}
+
+// Detail rows may record more than once, e.g. "嘉獎二次".
+fn detail_times(name: &str) -> u32 {
+    const NUMBERS: [&str; 3] = ["二", "三", "四"];
+
+    match NUMBERS.iter().position(|n| name.contains(n)) {
+        Some(i) => i as u32 + 2,
+        None if name.contains('兩') => 2,
+        None => 1
+    }
+}
+
+fn sum_status(status: &[RewardAndPunishStatus], types: &[String], ratio: u16) -> u32 {
+    status
+        .iter()
+        .filter_map(|s| type_weight(types, &s.r#type, ratio).map(|w| w * s.times as u32))
+        .sum()
+}
+
+// Express the balance in the biggest types first, e.g. 10 嘉獎 is 1 大功 and 1 嘉獎.
+fn to_balance(value: u32, types: &[String], ratio: u16) -> Vec<RewardAndPunishStatus> {
+    let mut remain = value;
+
+    types
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, name)| {
+            let weight = (ratio as u32).pow(i as u32);
+            let times = remain / weight;
+            remain %= weight;
+
+            RewardAndPunishStatus {
+                r#type: name.clone(),
+                times: times as u16
+            }
+        })
+        .collect()
+}
+
+fn calculate(status: &[RewardAndPunishStatus], detail: &[RewardAndPunishDetailValue], rule: &MeritRuleConfig) -> MeritBalanceCollect {
+    let merit = sum_status(status, &rule.merit, rule.ratio);
+    let demerit = sum_status(status, &rule.demerit, rule.ratio);
+
+    let cancelled = detail
+        .iter()
+        .filter(|d| d.sold.is_some())
+        .filter_map(|d| type_weight(&rule.demerit, &d.r#type, rule.ratio).map(|w| w * detail_times(&d.r#type)))
+        .sum::<u32>()
+        .min(demerit);
+
+    let net = merit as i32 - (demerit - cancelled) as i32;
+    let balance = if net >= 0 {
+        to_balance(net as u32, &rule.merit, rule.ratio)
+    } else {
+        to_balance(net.unsigned_abs(), &rule.demerit, rule.ratio)
+    };
+    let debt = if net < 0 { net.unsigned_abs() } else { 0 };
+
+    let cancellable = detail
+        .iter()
+        .filter(|d| d.sold.is_none() && rule.cancellable.iter().any(|c| d.r#type.contains(c.as_str())))
+        .cloned()
+        .collect();
+
+    MeritBalanceCollect {
+        merit,
+        demerit,
+        cancelled,
+        net,
+        balance,
+        expulsionRemain: (rule.expulsion as u32).saturating_sub(debt),
+        nearExpulsion: debt >= rule.warning as u32,
+        reachExpulsion: debt >= rule.expulsion as u32,
+        cancellable
+    }
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getMeritBalance",
+    tag = "Analytics",
+    responses(
+        (status = 200, description = "Merit balance", body = MeritBalanceData)
+    ),
+    security(("authToken" = []))
+)]
+#[get("/getMeritBalance")]
+pub async fn api(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<MeritBalanceData> {
+    let token = auth.0;
+
+    let data = get_reward_and_punish(API_PATH, &token).await?;
+
+    Ok(Custom(Status::Ok, Json(MeritBalanceData {
+        message: Message::GetMeritBalance,
+        data: calculate(&data.status, &data.detail, &read_config().merit_rule)
+    })))
+}
\ No newline at end of file
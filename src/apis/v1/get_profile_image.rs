@@ -0,0 +1,100 @@
+use lazy_static::lazy_static;
+use rocket::http::{Status, ContentType};
+
+use crate::{
+    i18n::Message,
+    request_handler::AuthorizationToken,
+    types::{AuthToken, HTTPResponse, ErrorReturn},
+    http::APIPaths,
+    utils::{self, combine_page_path, create_auth_header, generate_session_expire_error, http_get_err_handle, generate_parse_error},
+    apis::v1::get_user_profile::{get_image_id, fetch_image},
+    image_render::{resize_image_async, detect_image_type},
+    responder::FileResponse,
+    error::{HTTPError, ErrorCode},
+    cache::Cache
+};
+
+lazy_static! {
+    static ref IMAGE_CACHE: Cache<Vec<u8>> = Cache::new();
+}
+
+pub const PROFILE_IMAGE_SIZES: [u32; 3] = [64, 128, 256];
+
+const API_PATH: &str = "/v1/getProfileImage";
+fn error_message(status: Status, code: ErrorCode, message: Message, at: Option<&str>) -> ErrorReturn {
+    utils::error_message(API_PATH, status, code, message, at)
+}
+
+pub async fn get_origin_image(token: &AuthToken) -> HTTPResponse<Vec<u8>> {
+    let cache_key = format!("{}:{}", token.host, token.user_data.schoolNumber);
+
+    if let Some(image) = IMAGE_CACHE.get(&cache_key) {
+        return Ok(image)
+    }
+
+    let page = combine_page_path(&token.host, APIPaths::Profile);
+    let data = http_get_err_handle(API_PATH, &page, Some(create_auth_header(&token.cookie))).await?;
+
+    if !data.status().is_success() {
+        return Err(generate_session_expire_error(API_PATH))
+    }
+
+    let raw = data.text().await.map_err(|_| generate_parse_error(API_PATH, "Profile"))?;
+    let image = fetch_image(API_PATH, &token.host, &token.cookie, &get_image_id(API_PATH, &raw)?).await?;
+
+    IMAGE_CACHE.set(&cache_key, image.clone());
+
+    Ok(image)
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getProfileImage",
+    tag = "User",
+    responses(
+        (status = 200, description = "Profile image, PNG when resized", content_type = ["image/png", "image/jpeg", "image/gif"])
+    ),
+    security(("authToken" = []))
+)]
+#[get("/getProfileImage?<size>")]
+pub async fn api(auth: AuthorizationToken<AuthToken>, size: Option<u32>) -> HTTPResponse<FileResponse> {
+    let token = auth.0;
+
+    let size = match size {
+        Some(s) if PROFILE_IMAGE_SIZES.contains(&s) => s,
+        Some(_) => return Err(error_message(Status::BadRequest, ErrorCode::InvalidArgument, Message::UnsupportedImageSize, Some("Argument: size"))),
+        None => {
+            let image = get_origin_image(&token).await?;
+
+            return Ok(FileResponse {
+                content_type: detect_image_type(&image),
+                file: image,
+                file_name: None
+            })
+        }
+    };
+
+    let cache_key = format!("{}:{}:{}", token.host, token.user_data.schoolNumber, size);
+
+    if let Some(image) = IMAGE_CACHE.get(&cache_key) {
+        return Ok(FileResponse {
+            content_type: ContentType::PNG,
+            file: image,
+            file_name: None
+        })
+    }
+
+    let image = get_origin_image(&token).await?;
+    let resized = match resize_image_async(image, size).await {
+        Ok(r) => r,
+        Err(_) => return Err(error_message(Status::InternalServerError, ErrorCode::RenderFailed, HTTPError::ServerError.message(), Some("Resizing image")))
+    };
+
+    IMAGE_CACHE.set(&cache_key, resized.clone());
+
+    Ok(FileResponse {
+        content_type: ContentType::PNG,
+        file: resized,
+        file_name: None
+    })
+}
\ No newline at end of file
@@ -1,13 +1,16 @@
 use lazy_static::lazy_static;
-use rocket::{response::status::Custom, http::Status, serde::json::Json};
 use scraper::{Selector, Html};
 use tokio::join;
 
 use crate::{
+    i18n::Message,
     request_handler::AuthorizationToken,
-    types::{AuthToken, APIResponseJSON, RewardAndPunishData, RewardAndPunishStatus, RewardAndPunishDetailValue, RewardAndPunishCollect},
+    responder::ETagResponse,
+    etag::IfNoneMatch,
+    types::{AuthToken, RewardAndPunishData, RewardAndPunishStatus, RewardAndPunishDetailValue, RewardAndPunishCollect, HTTPResponse},
     http::APIPaths,
-    utils::{combine_page_path, create_auth_header, generate_session_expire_error, convert_string_to_u32, http_get_err_handle, html_to_text}
+    date::{parse_roc_date, academic_year_to_gregorian},
+    utils::{combine_page_path, create_auth_header, generate_session_expire_error, generate_parse_error, convert_string_to_u32, http_get_err_handle, html_to_text}
 };
 
 lazy_static! {
@@ -19,22 +22,19 @@ lazy_static! {
 
 const API_PATH: &str = "/v1/getRewAndPun";
 
-async fn get_summarize(html: &str) -> Vec<RewardAndPunishStatus> {
+// `None` when the page is not in the expected layout.
+async fn get_summarize(html: &str) -> Option<Vec<RewardAndPunishStatus>> {
     let html = Html::parse_document(&html);
     let mut data: Vec<RewardAndPunishStatus> = Vec::new();
 
     let select = html.select(&SUMMARIZE_TABLE).collect::<Vec<_>>();
-    let mut select = select
-        .get(select.len() - 2)
-        .unwrap()
+    let select = select
+        .get(select.len().checked_sub(2)?)?
         .select(&TR_SELECT)
-        .collect::<Vec<_>>();
-
-    select.remove(0);
+        .skip(1);
 
     for ele in select {
-        let mut tds = ele.select(&TD_SELECT).collect::<Vec<_>>();
-        tds.remove(0);
+        let tds = ele.select(&TD_SELECT).skip(1).collect::<Vec<_>>();
 
         for index in (1..tds.len()).step_by(2) {
             data.push(RewardAndPunishStatus {
@@ -44,10 +44,10 @@ async fn get_summarize(html: &str) -> Vec<RewardAndPunishStatus> {
         }
     }
 
-    data
+    Some(data)
 }
 
-async fn get_detail(html: &str) -> Vec<RewardAndPunishDetailValue> {
+async fn get_detail(html: &str) -> Option<Vec<RewardAndPunishDetailValue>> {
     let html = Html::parse_document(html);
     let mut data: Vec<RewardAndPunishDetailValue> = Vec::new();
     let select = html.select(&ROW_DATA).collect::<Vec<_>>();
@@ -57,39 +57,66 @@ async fn get_detail(html: &str) -> Vec<RewardAndPunishDetailValue> {
             .map(|s| html_to_text(s))
             .collect::<Vec<_>>();
 
+        if tds.len() < 7 {
+            return None
+        }
+
+        let sold = if tds[5].eq(&char::from_u32(0xa0).unwrap().to_string()) { None } else { Some(tds[5].clone()) };
+        let year = convert_string_to_u32(&tds[6]) as u16;
+
         data.push(RewardAndPunishDetailValue {
             r#type: tds[0].clone(),
             start: tds[1].clone(),
             signed: tds[2].clone(),
             reason: tds[3].clone(),
             execute: tds[4].clone(),
-            sold: if tds[5].eq(&char::from_u32(0xa0).unwrap().to_string()) { None } else { Some(tds[5].clone()) },
-            year: convert_string_to_u32(&tds[6]) as u16
+            isoStart: parse_roc_date(&tds[1]),
+            isoSigned: parse_roc_date(&tds[2]),
+            isoExecute: parse_roc_date(&tds[4]),
+            isoSold: sold.as_deref().and_then(parse_roc_date),
+            sold,
+            gregorianYear: academic_year_to_gregorian(year),
+            year
         });
     }
 
-    data
+    Some(data)
 }
 
-#[get("/getRewAndPun")]
-pub async fn api(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<RewardAndPunishData> {
-    let token = auth.0;
-
+pub async fn get_reward_and_punish(api: &str, token: &AuthToken) -> HTTPResponse<RewardAndPunishCollect> {
     let page = combine_page_path(&token.host, APIPaths::RewardAndPunish);
 
-    let data = http_get_err_handle(API_PATH, &page, Some(create_auth_header(&token.cookie))).await?;
+    let data = http_get_err_handle(api, &page, Some(create_auth_header(&token.cookie))).await?;
     if !data.status().is_success() {
-        return Err(generate_session_expire_error(API_PATH))
+        return Err(generate_session_expire_error(api))
     }
 
-    let raw = data.text().await.unwrap();
-    let (summarize, detail) = join!(get_summarize(&raw), get_detail(&raw));
+    let raw = data.text().await.map_err(|_| generate_parse_error(api, "Reward and punish page"))?;
 
-    Ok(Custom(Status::Ok, Json(RewardAndPunishData {
-        message: "Get reward and punish successful".to_owned(),
-        data: RewardAndPunishCollect {
-            status: summarize,
-            detail
-        }
-    })))
+    match join!(get_summarize(&raw), get_detail(&raw)) {
+        (Some(status), Some(detail)) => Ok(RewardAndPunishCollect { status, detail }),
+        _ => Err(generate_parse_error(api, "Reward and punish table"))
+    }
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getRewAndPun",
+    tag = "User",
+    responses(
+        (status = 200, description = "Reward and punish records", body = RewardAndPunishData),
+        (status = 304, description = "Not modified since the `ETag` in `If-None-Match`")
+    ),
+    security(("authToken" = []))
+)]
+#[get("/getRewAndPun")]
+pub async fn api(auth: AuthorizationToken<AuthToken>, if_none_match: IfNoneMatch) -> HTTPResponse<ETagResponse> {
+    let token = auth.0;
+
+    let data = get_reward_and_punish(API_PATH, &token).await?;
+
+    Ok(if_none_match.respond(&token, API_PATH, &RewardAndPunishData {
+        message: Message::GetRewardAndPunish,
+        data
+    }))
 }
\ No newline at end of file
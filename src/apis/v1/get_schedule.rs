@@ -0,0 +1,189 @@
+use lazy_static::lazy_static;
+use rocket::{response::status::Custom, http::Status};
+use scraper::{Selector, ElementRef};
+use url::Url;
+
+use crate::{
+    responder::Json,
+    i18n::Message,
+    request_handler::AuthorizationToken,
+    types::{AuthToken, APIResponseJSON, HTTPResponse, ErrorReturn, ScheduleListValues, ScheduleListCollect, ScheduleListData, ScheduleValue, ScheduleCollect, ScheduleData, LackPeriod},
+    http::{APIPaths, ReplaceString},
+    apis::v1::get_lack::decode_period,
+    utils::{self, combine_path, combine_page_path, create_auth_header, generate_session_expire_error, http_get_html_err_handle, html_to_text, find_string_in_url},
+    error::ErrorCode
+};
+
+lazy_static! {
+    static ref LIST_SELECTOR: Selector = Selector::parse("option").unwrap();
+    static ref TABLE_SELECTOR: Selector = Selector::parse("table").unwrap();
+    static ref TR_SELECTOR: Selector = Selector::parse("tr").unwrap();
+    static ref TD_SELECTOR: Selector = Selector::parse("td").unwrap();
+}
+
+const API_PATH: &str = "/v1/getSchedule";
+const LIST_API_PATH: &str = "/v1/getScheduleList";
+fn error_message(status: Status, code: ErrorCode, message: Message, at: Option<&str>) -> ErrorReturn {
+    utils::error_message(API_PATH, status, code, message, at)
+}
+
+pub async fn get_schedule_list(api: &str, token: &AuthToken) -> HTTPResponse<Vec<ScheduleListValues>> {
+    let page = combine_page_path(&token.host, APIPaths::ScheduleList);
+    let data = http_get_html_err_handle(api, &page, Some(create_auth_header(&token.cookie))).await?;
+
+    if !data.code.is_success() {
+        return Err(generate_session_expire_error(api))
+    }
+
+    let list = data.html
+        .select(&LIST_SELECTOR)
+        .filter_map(|ele| {
+            let value = ele.value().attr("value")?;
+            let url = Url::parse(&format!("http://example.com/{}", value.replace("../", ""))).ok()?;
+            let params = url.query_pairs();
+            let class = find_string_in_url(&params, "teacher_classnumber");
+
+            if class.is_empty() {
+                return None
+            }
+
+            Some(ScheduleListValues {
+                name: html_to_text(ele).trim().to_owned(),
+                teacher: find_string_in_url(&params, "teacher_name"),
+                class
+            })
+        })
+        .collect();
+
+    Ok(list)
+}
+
+fn cell_texts(ele: ElementRef<'_>) -> Vec<String> {
+    ele
+        .text()
+        .map(|t| t.trim().to_owned())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+// Rows of the timetable are periods and columns are weekdays, from Monday.
+pub async fn get_schedule(api: &str, token: &AuthToken, class: &str, teacher: &str) -> HTTPResponse<ScheduleCollect> {
+    let page = combine_path(&token.host, &APIPaths::Schedule.replace(vec![
+        ReplaceString {
+            match_string: "$class$".to_owned(),
+            replacement: class.to_owned()
+        },
+        ReplaceString {
+            match_string: "$teacher$".to_owned(),
+            replacement: teacher.to_owned()
+        }
+    ]));
+    let data = http_get_html_err_handle(api, &page, Some(create_auth_header(&token.cookie))).await?;
+
+    if !data.code.is_success() {
+        return Err(generate_session_expire_error(api))
+    }
+
+    let table = data.html
+        .select(&TABLE_SELECTOR)
+        .rfind(|t| html_to_text(*t).contains("星期"));
+    let mut schedule: Vec<ScheduleValue> = Vec::new();
+
+    if let Some(table) = table {
+        for row in table.select(&TR_SELECTOR) {
+            let tds = row.select(&TD_SELECTOR).collect::<Vec<_>>();
+            let label = match tds.first().and_then(|td| cell_texts(*td).into_iter().next()) {
+                Some(label) => label,
+                None => continue
+            };
+
+            let period = match decode_period(&label) {
+                LackPeriod::Class(p) => p,
+                _ => continue
+            };
+
+            for (index, td) in tds.iter().skip(1).enumerate() {
+                let mut texts = cell_texts(*td).into_iter();
+
+                if let Some(subject) = texts.next() {
+                    schedule.push(ScheduleValue {
+                        weekday: index as u8 + 1,
+                        period,
+                        periodLabel: label.clone(),
+                        subject,
+                        teacher: texts.next()
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(ScheduleCollect {
+        class: class.to_owned(),
+        teacher: teacher.to_owned(),
+        schedule
+    })
+}
+
+// The timetable of the class of the user.
+pub async fn get_own_schedule(api: &str, token: &AuthToken) -> HTTPResponse<ScheduleCollect> {
+    let list = get_schedule_list(api, token).await?;
+    let class_name = &token.user_data.className;
+
+    let own = list
+        .iter()
+        .find(|s| &s.class == class_name || s.name.contains(class_name.as_str()));
+
+    match own {
+        Some(s) => get_schedule(api, token, &s.class, &s.teacher).await,
+        None => Err(utils::error_message(api, Status::NotFound, ErrorCode::NotFound, Message::ScheduleNotFound, None))
+    }
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getScheduleList",
+    tag = "User",
+    responses(
+        (status = 200, description = "Classes and teachers with a schedule", body = ScheduleListData)
+    ),
+    security(("authToken" = []))
+)]
+#[get("/getScheduleList")]
+pub async fn api_list(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<ScheduleListData> {
+    let token = auth.0;
+
+    let schedules = get_schedule_list(LIST_API_PATH, &token).await?;
+
+    Ok(Custom(Status::Ok, Json(ScheduleListData {
+        message: Message::GetScheduleList,
+        data: ScheduleListCollect {
+            schedules
+        }
+    })))
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getSchedule",
+    tag = "User",
+    responses(
+        (status = 200, description = "Schedule of the class and teacher, or of the user", body = ScheduleData)
+    ),
+    security(("authToken" = []))
+)]
+#[get("/getSchedule?<class>&<teacher>")]
+pub async fn api(auth: AuthorizationToken<AuthToken>, class: Option<&str>, teacher: Option<&str>) -> APIResponseJSON<ScheduleData> {
+    let token = auth.0;
+
+    let data = match (class, teacher) {
+        (Some(class), Some(teacher)) => get_schedule(API_PATH, &token, class, teacher).await?,
+        (None, None) => get_own_schedule(API_PATH, &token).await?,
+        _ => return Err(error_message(Status::BadRequest, ErrorCode::MissingArgument, Message::MissingArguments, Some("Arguments")))
+    };
+
+    Ok(Custom(Status::Ok, Json(ScheduleData {
+        message: Message::GetSchedule,
+        data
+    })))
+}
\ No newline at end of file
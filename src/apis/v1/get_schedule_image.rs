@@ -0,0 +1,63 @@
+use rocket::http::{Status, ContentType};
+
+use crate::{
+    i18n::Message,
+    request_handler::AuthorizationToken,
+    types::{AuthToken, HTTPResponse, ErrorReturn},
+    apis::v1::get_schedule::{get_schedule, get_own_schedule},
+    timetable::{TimetableTheme, TimetableDevice, MIN_IMAGE_SIZE, MAX_IMAGE_SIZE, create_timetable_svg},
+    image_render::render_svg_to_png_async,
+    responder::FileResponse,
+    config::read_config,
+    error::{HTTPError, ErrorCode},
+    utils
+};
+
+const API_PATH: &str = "/v1/getScheduleImage";
+fn error_message(status: Status, code: ErrorCode, message: Message, at: Option<&str>) -> ErrorReturn {
+    utils::error_message(API_PATH, status, code, message, at)
+}
+
+// `width` and `height` take priority over `device`, the default is a phone in light theme.
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getScheduleImage",
+    tag = "User",
+    responses(
+        (status = 200, description = "Timetable image", content_type = "image/png")
+    ),
+    security(("authToken" = []))
+)]
+#[get("/getScheduleImage?<class>&<teacher>&<theme>&<device>&<width>&<height>")]
+pub async fn api(auth: AuthorizationToken<AuthToken>, class: Option<&str>, teacher: Option<&str>, theme: Option<TimetableTheme>, device: Option<TimetableDevice>, width: Option<u32>, height: Option<u32>) -> HTTPResponse<FileResponse> {
+    let token = auth.0;
+
+    let (width, height) = match (width, height) {
+        (Some(w), Some(h)) => (w, h),
+        (None, None) => device.unwrap_or(TimetableDevice::Phone).size(),
+        _ => return Err(error_message(Status::BadRequest, ErrorCode::MissingArgument, Message::MissingArguments, Some("Arguments: width, height")))
+    };
+
+    let size_range = MIN_IMAGE_SIZE..=MAX_IMAGE_SIZE;
+    if !size_range.contains(&width) || !size_range.contains(&height) {
+        return Err(error_message(Status::BadRequest, ErrorCode::InvalidArgument, Message::UnsupportedImageSize, Some("Arguments: width, height")))
+    }
+
+    let data = match (class, teacher) {
+        (Some(class), Some(teacher)) => get_schedule(API_PATH, &token, class, teacher).await?,
+        (None, None) => get_own_schedule(API_PATH, &token).await?,
+        _ => return Err(error_message(Status::BadRequest, ErrorCode::MissingArgument, Message::MissingArguments, Some("Arguments")))
+    };
+
+    let svg = create_timetable_svg(&data.schedule, &read_config().period_times, width, height, theme.unwrap_or(TimetableTheme::Light));
+    let image = match render_svg_to_png_async(svg).await {
+        Ok(image) => image,
+        Err(_) => return Err(error_message(Status::InternalServerError, ErrorCode::RenderFailed, HTTPError::ServerError.message(), Some("Rendering timetable")))
+    };
+
+    Ok(FileResponse {
+        content_type: ContentType::PNG,
+        file: image,
+        file_name: None
+    })
+}
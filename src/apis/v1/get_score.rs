@@ -1,12 +1,15 @@
 use lazy_static::lazy_static;
-use rocket::{response::status::Custom, serde::json::Json, http::Status};
+use rocket::{response::status::Custom, http::Status};
 use scraper::Selector;
 
 use crate::{
+    responder::Json,
+    i18n::Message,
     request_handler::AuthorizationToken,
-    types::{AuthToken, APIResponseJSON, ScoreData, ErrorReturn, ScoreDataValue, ScoreUnpass, ScoreExtraData, ScoreDataCollect},
-    utils::{self, combine_path, create_auth_header, http_get_html_err_handle, html_to_text, convert_string_to_u32, convert_string_to_f32},
-    http::{APIPaths, ReplaceString}
+    types::{AuthToken, APIResponseJSON, ScoreData, ErrorReturn, ScoreDataValue, ScoreUnpass, ScoreExtraData, ScoreDataCollect, ScoreValue, HTTPResponse},
+    utils::{self, combine_path, create_auth_header, http_get_html_err_handle, html_to_text, generate_parse_error},
+    http::{APIPaths, ReplaceString},
+    error::ErrorCode
 };
 
 lazy_static! {
@@ -17,107 +20,116 @@ lazy_static! {
 }
 
 const API_PATH: &str = "/v1/getScoreInfo";
-fn error_message(code: Status, message: &str, at: Option<&str>) -> ErrorReturn {
-    utils::error_message(API_PATH, code, message, at)
+fn error_message(status: Status, code: ErrorCode, message: Message, at: Option<&str>) -> ErrorReturn {
+    utils::error_message(API_PATH, status, code, message, at)
 }
 
-#[derive(Debug, FromForm)]
-#[allow(non_snake_case)]
-pub struct DataStruct {
-    pub year: String,
-    pub term: String,
-    pub times: String,
-    pub testID: String
+const ABSENT_KEYWORDS: [&str; 3] = ["缺考", "缺席", "未到"];
+const EXEMPT_KEYWORDS: [&str; 4] = ["免修", "免考", "免試", "抵免"];
+
+// A subject of the score page.
+pub struct ScoreRecord {
+    pub name: String,
+    pub score: ScoreValue,
+    pub gpa: ScoreValue,
+    pub score_unpass: bool,
+    pub gpa_unpass: bool
 }
 
-#[get("/getScoreInfo?<params..>")]
-#[allow(non_snake_case)]
-pub async fn api(auth: AuthorizationToken<AuthToken>, params: Option<DataStruct>) -> APIResponseJSON<ScoreData> {
-    let token = auth.0;
-    let params = match params {
-        Some(param) => param,
-        None => return Err(error_message(Status::BadRequest, "Missing one or more arguments", Some("Arguments")))
-    };
+impl ScoreValue {
+    // Text which is neither a number nor a known marker is taken as not graded yet.
+    pub fn parse(raw: &str) -> Self {
+        let raw = raw.trim().to_owned();
+
+        if let Ok(value) = raw.parse::<f32>() {
+            return ScoreValue::Numeric { value, raw }
+        }
+        if ABSENT_KEYWORDS.iter().any(|k| raw.contains(k)) {
+            return ScoreValue::Absent { raw }
+        }
+        if EXEMPT_KEYWORDS.iter().any(|k| raw.contains(k)) {
+            return ScoreValue::Exempt { raw }
+        }
+
+        ScoreValue::NotGraded { raw }
+    }
 
+    pub fn number(&self) -> Option<f32> {
+        match self {
+            ScoreValue::Numeric { value, .. } => Some(*value),
+            _ => None
+        }
+    }
+
+    pub fn raw(&self) -> &str {
+        match self {
+            ScoreValue::Numeric { raw, .. } | ScoreValue::Absent { raw } | ScoreValue::Exempt { raw } | ScoreValue::NotGraded { raw } => raw
+        }
+    }
+}
+
+fn cell_text(ele: scraper::ElementRef<'_>) -> String {
+    html_to_text(ele)
+        .replace(" ", "")
+        .replace("\r\n", "")
+        .replace("\n", "")
+}
+
+pub async fn fetch_score(api: &str, token: &AuthToken, year: &str, term: &str, test_id: &str) -> HTTPResponse<(Vec<ScoreRecord>, Vec<ScoreExtraData>)> {
     let page = combine_path(&token.host, &APIPaths::Score.replace(vec![
         ReplaceString {
             match_string: "$year$".to_owned(),
-            replacement: params.year
+            replacement: year.to_owned()
         },
         ReplaceString {
             match_string: "$term$".to_owned(),
-            replacement: params.term
+            replacement: term.to_owned()
         },
         ReplaceString {
             match_string: "$testid$".to_owned(),
-            replacement: params.testID
+            replacement: test_id.to_owned()
         }
     ]));
 
-    let data = http_get_html_err_handle(API_PATH, &page, Some(create_auth_header(&token.cookie))).await?;
+    let data = http_get_html_err_handle(api, &page, Some(create_auth_header(&token.cookie))).await?;
 
     if data.html.html().contains("尚未開放") {
-        return Err(error_message(Status::NotFound, "Cannot find the score data", None))
+        return Err(utils::error_message(api, Status::NotFound, ErrorCode::NotFound, Message::ScoreNotFound, None))
     }
 
-    let mut score_list: Vec<ScoreDataValue> = Vec::new();
-    let mut unpass_list: Vec<ScoreUnpass> = Vec::new();
+    let mut score_list: Vec<ScoreRecord> = Vec::new();
 
-    let mut table_data = data.html
+    let table_data = data.html
         .select(&TABLE_SELECT)
         .collect::<Vec<_>>();
 
-    table_data.remove(0);
+    if table_data.is_empty() {
+        return Err(generate_parse_error(api, "Score table"))
+    }
 
-    for ele in table_data {
+    for ele in table_data.into_iter().skip(1) {
         let list = ele.select(&TDS_SELECT).collect::<Vec<_>>();
 
-        let is_avaiable = match list.get(1) {
-            Some(_) => true,
-            None => false
-        };
-        
-        if list.len() > 0 && is_avaiable {
-            let score_name = html_to_text(list[0]).replace(" ", "");
-            let element_score = list[1].select(&SPAN_SELECT).next().unwrap();
-            let element_gpa = list[2].select(&SPAN_SELECT).next().unwrap();
-
-            let score_numb = convert_string_to_u32(&html_to_text(element_score)
-                .replace(" ", "")
-                .replace("\r\n", "")
-                .replace("\n", "")) as u8;
-            let score_gpa = convert_string_to_f32(&html_to_text(element_gpa)
-                .replace(" ", "")
-                .replace("\r\n", "")
-                .replace("\n", ""));
-
-            score_list.push(ScoreDataValue {
-                name: score_name.clone(),
-                score: score_numb,
-                gpa: score_gpa
+        if list.len() > 2 {
+            let (element_score, element_gpa) = match (list[1].select(&SPAN_SELECT).next(), list[2].select(&SPAN_SELECT).next()) {
+                (Some(score), Some(gpa)) => (score, gpa),
+                _ => return Err(generate_parse_error(api, "Score table"))
+            };
+
+            score_list.push(ScoreRecord {
+                name: html_to_text(list[0]).replace(" ", ""),
+                score: ScoreValue::parse(&cell_text(element_score)),
+                gpa: ScoreValue::parse(&cell_text(element_gpa)),
+                score_unpass: element_score.value().attr("style").is_some_and(|s| s.contains("red")),
+                gpa_unpass: element_gpa.value().has_class("unpass", scraper::CaseSensitivity::AsciiCaseInsensitive)
             });
-
-            if let Some(data) = element_score.value().attr("style") {
-                if data.contains("red") {
-                    unpass_list.push(ScoreUnpass {
-                        r#type: "score".to_owned(),
-                        name: score_name.clone()
-                    });
-                }
-            }
-            if element_gpa.value().has_class("unpass", scraper::CaseSensitivity::AsciiCaseInsensitive) {
-                unpass_list.push(ScoreUnpass {
-                    r#type: "gpa".to_owned(),
-                    name: score_name.clone()
-                });
-            }
         }
     }
 
     let mut extra_list: Vec<ScoreExtraData> = Vec::new();
     let extra_info = data.html.select(&EXTRA_SELECT).collect::<Vec<_>>();
 
-    for i in 0..extra_info.len() {
+    for i in 1..extra_info.len() {
         if extra_info[i].value().has_class("score", scraper::CaseSensitivity::AsciiCaseInsensitive) {
             extra_list.push(ScoreExtraData {
                 r#type: html_to_text(extra_info[i - 1]).replace("：", ""),
@@ -126,12 +138,67 @@ pub async fn api(auth: AuthorizationToken<AuthToken>, params: Option<DataStruct>
         }
     }
 
-    Ok(Custom(Status::Ok, Json(ScoreData {
-        message: "Get score info successful".to_owned(),
-        data: ScoreDataCollect {
-            data: score_list,
-            extra: extra_list,
-            unpass: unpass_list
+    Ok((score_list, extra_list))
+}
+
+pub async fn get_score(api: &str, token: &AuthToken, year: &str, term: &str, test_id: &str) -> HTTPResponse<ScoreDataCollect> {
+    let (records, extra) = fetch_score(api, token, year, term, test_id).await?;
+
+    let mut data: Vec<ScoreDataValue> = Vec::new();
+    let mut unpass: Vec<ScoreUnpass> = Vec::new();
+
+    for record in records {
+        if record.score_unpass {
+            unpass.push(ScoreUnpass {
+                r#type: "score".to_owned(),
+                name: record.name.clone()
+            });
         }
+        if record.gpa_unpass {
+            unpass.push(ScoreUnpass {
+                r#type: "gpa".to_owned(),
+                name: record.name.clone()
+            });
+        }
+
+        data.push(ScoreDataValue {
+            name: record.name,
+            score: record.score.number().map_or(0, |v| v.round() as u8),
+            gpa: record.gpa.number().unwrap_or(0.0),
+            scoreValue: record.score,
+            gpaValue: record.gpa
+        });
+    }
+
+    Ok(ScoreDataCollect {
+        data,
+        extra,
+        unpass
+    })
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getScoreInfo",
+    tag = "User",
+    responses(
+        (status = 200, description = "Score of the exam", body = ScoreData)
+    ),
+    security(("authToken" = []))
+)]
+#[get("/getScoreInfo?<year>&<term>&<times>&<testID>")]
+#[allow(non_snake_case)]
+pub async fn api(auth: AuthorizationToken<AuthToken>, year: Option<String>, term: Option<String>, times: Option<String>, testID: Option<String>) -> APIResponseJSON<ScoreData> {
+    let token = auth.0;
+    let (year, term, test_id) = match (year, term, times, testID) {
+        (Some(year), Some(term), Some(_), Some(test_id)) => (year, term, test_id),
+        _ => return Err(error_message(Status::BadRequest, ErrorCode::MissingArgument, Message::MissingArguments, Some("Arguments")))
+    };
+
+    let data = get_score(API_PATH, &token, &year, &term, &test_id).await?;
+
+    Ok(Custom(Status::Ok, Json(ScoreData {
+        message: Message::GetScore,
+        data
     })))
 }
\ No newline at end of file
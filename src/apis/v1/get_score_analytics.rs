@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+use rocket::{response::status::Custom, http::Status, futures::future::join_all};
+
+use crate::{
+    responder::Json,
+    i18n::Message,
+    request_handler::AuthorizationToken,
+    types::{AuthToken, APIResponseJSON, HTTPResponse, ScoreAnalyticsData, ScoreAnalyticsCollect, ScoreAnalyticsTest, ScoreSubjectTrend, ScoreTrendPoint, ScoreDataCollect, AvailableScoreValue},
+    apis::v1::{get_available_score::get_available_score, get_score::get_score},
+    utils::convert_string_to_f32
+};
+
+const API_PATH: &str = "/v1/getScoreAnalytics";
+const WEIGHTED_AVERAGE_LABEL: &str = "加權平均";
+
+fn average(values: &[f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0
+    }
+
+    values.iter().sum::<f32>() / values.len() as f32
+}
+
+pub fn analyze_test(info: &AvailableScoreValue, score: &ScoreDataCollect) -> ScoreAnalyticsTest {
+    // Absent, exempt and not graded subjects are neither passed nor failed.
+    let graded = score.data.iter().filter(|d| d.scoreValue.number().is_some()).collect::<Vec<_>>();
+    let fail = graded
+        .iter()
+        .filter(|d| score.unpass.iter().any(|u| u.r#type == "score" && u.name == d.name))
+        .count() as u16;
+    let weighted = score.extra
+        .iter()
+        .find(|e| e.r#type.contains(WEIGHTED_AVERAGE_LABEL))
+        .map(|e| convert_string_to_f32(&e.value));
+
+    ScoreAnalyticsTest {
+        name: info.name.clone(),
+        testID: info.testID.clone(),
+        year: info.year,
+        gregorianYear: info.gregorianYear,
+        term: info.term,
+        times: info.times,
+        r#type: info.r#type,
+        average: average(&graded.iter().filter_map(|d| d.scoreValue.number()).collect::<Vec<_>>()),
+        weightedAverage: weighted,
+        pass: graded.len() as u16 - fail,
+        fail
+    }
+}
+
+pub fn analyze_subjects(scores: &[(AvailableScoreValue, ScoreDataCollect)]) -> Vec<ScoreSubjectTrend> {
+    let mut subjects: BTreeMap<String, Vec<ScoreTrendPoint>> = BTreeMap::new();
+
+    for (info, score) in scores {
+        // Absent, exempt and not graded subjects are not part of the trend.
+        for (data, value) in score.data.iter().filter_map(|d| d.scoreValue.number().map(|v| (d, v))) {
+            subjects.entry(data.name.clone()).or_default().push(ScoreTrendPoint {
+                year: info.year,
+                gregorianYear: info.gregorianYear,
+                term: info.term,
+                times: info.times,
+                score: value
+            });
+        }
+    }
+
+    subjects
+        .into_iter()
+        .map(|(name, mut points)| {
+            points.sort_by_key(|p| (p.year, p.term, p.times));
+
+            let change = match points.len() {
+                0 | 1 => None,
+                len => Some(points[len - 1].score - points[len - 2].score)
+            };
+
+            ScoreSubjectTrend {
+                name,
+                average: average(&points.iter().map(|p| p.score).collect::<Vec<_>>()),
+                change,
+                points
+            }
+        })
+        .collect()
+}
+
+// Every opened exam is fetched concurrently. Exams which are not opened yet are skipped.
+pub async fn get_opened_scores(api: &str, token: &AuthToken) -> HTTPResponse<Vec<(AvailableScoreValue, ScoreDataCollect)>> {
+    let list = get_available_score(api, token).await?;
+    let params = list
+        .iter()
+        .map(|info| (info.year.to_string(), info.term.to_string()))
+        .collect::<Vec<_>>();
+    let results = join_all(list
+        .iter()
+        .zip(&params)
+        .map(|(info, (year, term))| get_score(api, token, year, term, &info.testID)))
+        .await;
+
+    let mut scores: Vec<(AvailableScoreValue, ScoreDataCollect)> = Vec::new();
+
+    for (info, result) in list.into_iter().zip(results) {
+        match result {
+            Ok(score) => scores.push((info, score)),
+            Err(err) if err.0 == Status::NotFound => continue,
+            Err(err) => return Err(err)
+        }
+    }
+
+    Ok(scores)
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getScoreAnalytics",
+    tag = "Analytics",
+    responses(
+        (status = 200, description = "Score trends of every opened exam", body = ScoreAnalyticsData)
+    ),
+    security(("authToken" = []))
+)]
+#[get("/getScoreAnalytics")]
+pub async fn api(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<ScoreAnalyticsData> {
+    let token = auth.0;
+
+    let scores = get_opened_scores(API_PATH, &token).await?;
+    let tests = scores
+        .iter()
+        .map(|(info, score)| analyze_test(info, score))
+        .collect::<Vec<_>>();
+    let subjects = analyze_subjects(&scores);
+
+    // Each exam is weighted by its number of subjects, which equals the average of every subject score.
+    let all_scores = scores
+        .iter()
+        .flat_map(|(_, score)| score.data.iter().filter_map(|d| d.scoreValue.number()))
+        .collect::<Vec<_>>();
+    let weighted = tests
+        .iter()
+        .filter_map(|t| t.weightedAverage)
+        .collect::<Vec<_>>();
+
+    Ok(Custom(Status::Ok, Json(ScoreAnalyticsData {
+        message: Message::GetScoreAnalytics,
+        data: ScoreAnalyticsCollect {
+            average: average(&all_scores),
+            meanWeightedAverage: if weighted.is_empty() { None } else { Some(average(&weighted)) },
+            pass: tests.iter().map(|t| t.pass).sum(),
+            fail: tests.iter().map(|t| t.fail).sum(),
+            tests,
+            subjects
+        }
+    })))
+}
\ No newline at end of file
@@ -0,0 +1,62 @@
+use rocket::http::{Status, ContentType};
+use tokio::join;
+
+use crate::{
+    request_handler::AuthorizationToken,
+    types::{AuthToken, HTTPResponse},
+    apis::v1::{get_all_scores::get_all_scores, get_lack::get_lack, get_profile_image::get_origin_image},
+    transcript::{create_transcript_pages, render_transcript_async},
+    date::{civil_from_days, get_local_days},
+    responder::FileResponse,
+    config::read_config,
+    error::{HTTPError, ErrorCode},
+    utils
+};
+
+const API_PATH: &str = "/v1/getTranscript";
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getTranscript",
+    tag = "Export",
+    responses(
+        (status = 200, description = "Transcript", content_type = "application/pdf")
+    ),
+    security(("authToken" = []))
+)]
+#[get("/getTranscript")]
+pub async fn api(auth: AuthorizationToken<AuthToken>) -> HTTPResponse<FileResponse> {
+    let token = auth.0;
+
+    let (scores, lack, photo) = join!(
+        get_all_scores(API_PATH, &token),
+        get_lack(API_PATH, &token),
+        get_origin_image(&token)
+    );
+
+    let scores = scores?;
+
+    // The transcript is still useful without the photo.
+    let photo = photo.ok().filter(|p| !p.is_empty());
+
+    let (year, month, day) = civil_from_days(get_local_days());
+    let document = create_transcript_pages(
+        &token.user_data,
+        photo.as_deref(),
+        &scores,
+        &lack?.total,
+        &format!("{:04}-{:02}-{:02}", year, month, day),
+        &read_config().provider
+    );
+
+    let pdf = match render_transcript_async(document).await {
+        Ok(pdf) => pdf,
+        Err(_) => return Err(utils::error_message(API_PATH, Status::InternalServerError, ErrorCode::RenderFailed, HTTPError::ServerError.message(), Some("Rendering transcript")))
+    };
+
+    Ok(FileResponse {
+        content_type: ContentType::PDF,
+        file: pdf,
+        file_name: Some(format!("transcript-{}.pdf", token.user_data.schoolNumber))
+    })
+}
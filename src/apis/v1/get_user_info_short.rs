@@ -1,12 +1,14 @@
 use lazy_static::lazy_static;
-use rocket::{http::Status, response::status::Custom, serde::json::Json};
+use rocket::{http::Status, response::status::Custom};
 use scraper::Selector;
 use tokio::join;
 
 use crate::{
+    responder::Json,
+    i18n::Message,
     request_handler::AuthorizationToken,
-    utils::{combine_page_path, create_auth_header, html_to_text},
-    types::{UserProfileShortValue, APIResponseJSON, UserProfileShortData, AuthToken, LoginInfoAuthToken},
+    utils::{combine_page_path, create_auth_header, html_to_text, generate_parse_error, generate_session_expire_error},
+    types::{UserProfileShortValue, APIResponseJSON, UserProfileShortData, AuthToken, LoginInfoAuthToken, HTTPResponse},
     http::{APIPaths, http_get_html},
     error::FetchError
 };
@@ -26,7 +28,8 @@ struct UserInfo {
 async fn fetch_class(host: &str, cookie: &str) -> Result<String, FetchError> {
     let url = combine_page_path(host, APIPaths::ClassData);
     let data = match http_get_html(&url, Some(create_auth_header(cookie))).await {
-        Ok(r) => r.html,
+        Ok(r) if r.code.is_success() => r.html,
+        Ok(_) => return Err(FetchError::AuthError),
         Err(_) => return Err(FetchError::FetchFailed)
     };
 
@@ -50,7 +53,8 @@ async fn fetch_class(host: &str, cookie: &str) -> Result<String, FetchError> {
 async fn fetch_user(host: &str, cookie: &str) -> Result<UserInfo, FetchError> {
     let url = combine_page_path(host, APIPaths::ProfileShort);
     let data = match http_get_html(&url, Some(create_auth_header(cookie))).await {
-        Ok(r) => r.html,
+        Ok(r) if r.code.is_success() => r.html,
+        Ok(_) => return Err(FetchError::AuthError),
         Err(_) => return Err(FetchError::FetchFailed)
     };
 
@@ -61,40 +65,54 @@ async fn fetch_user(host: &str, cookie: &str) -> Result<UserInfo, FetchError> {
                 .replace(" ", "")
                 .replace("\n", "")
         });
+    let mut next = || data.next().ok_or(FetchError::FetchFailed);
 
-    let output = UserInfo {
-        class_number: data.next().unwrap(),
-        school_number: data.next().unwrap(),
-        user_name: data.next().unwrap(),
-        gender: data.next().unwrap()
-    };
-
-    Ok(output)
+    Ok(UserInfo {
+        class_number: next()?,
+        school_number: next()?,
+        user_name: next()?,
+        gender: next()?
+    })
 }
 
-async fn get_info_from_web(token: LoginInfoAuthToken) -> Result<UserProfileShortValue, FetchError> {
-    let (class, user) = join!(fetch_class(&token.host, &token.cookie), fetch_user(&token.host, &token.cookie));
+async fn get_info_from_web(host: &str, cookie: &str) -> Result<UserProfileShortValue, FetchError> {
+    let (class, user) = join!(fetch_class(host, cookie), fetch_user(host, cookie));
+    let user_data = user?;
 
-    match (class, user) {
-        (Ok(class_data), Ok(user_data)) => return Ok(UserProfileShortValue {
-            className: class_data,
-            classNumber: user_data.class_number,
-            gender: user_data.gender,
-            schoolNumber: user_data.school_number,
-            userName: user_data.user_name
-        }),
-        _ => return Err(FetchError::AuthError)
-    }
+    Ok(UserProfileShortValue {
+        className: class?,
+        classNumber: user_data.class_number,
+        gender: user_data.gender,
+        schoolNumber: user_data.school_number,
+        userName: user_data.user_name
+    })
 }
 
 pub async fn get_user_info_short(token: LoginInfoAuthToken) -> Result<UserProfileShortValue, FetchError> {
-    Ok(get_info_from_web(token).await?)
+    Ok(get_info_from_web(&token.host, &token.cookie).await?)
+}
+
+// Read again from the school server, unlike `api` which answers the profile kept in the token.
+pub async fn fetch_user_info_short(api: &str, token: &AuthToken) -> HTTPResponse<UserProfileShortValue> {
+    get_info_from_web(&token.host, &token.cookie).await.map_err(|err| match err {
+        FetchError::AuthError => generate_session_expire_error(api),
+        FetchError::FetchFailed => generate_parse_error(api, "User data")
+    })
 }
 
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getUserInfoShort",
+    tag = "User",
+    responses(
+        (status = 200, description = "Short profile", body = UserProfileShortData)
+    ),
+    security(("authToken" = []))
+)]
 #[get("/getUserInfoShort")]
 pub async fn api(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<UserProfileShortData> {
     Ok(Custom(Status::Ok, Json(UserProfileShortData {
-        message: "Get user profile short successful".to_owned(),
+        message: Message::GetUserProfileShort,
         data: auth.0.user_data
     })))
 }
\ No newline at end of file
@@ -1,14 +1,19 @@
+use std::collections::BTreeMap;
 use lazy_static::lazy_static;
-use rocket::{http::Status, response::status::Custom, serde::json::Json};
+use rocket::{http::Status, response::status::Custom};
 use scraper::{Selector, Html};
 use tokio::join;
 use url::Url;
 
 use crate::{
+    responder::Json,
+    i18n::Message,
     request_handler::AuthorizationToken,
-    types::{APIResponseJSON, UserData, AuthToken, UserDataValues, UserCollect},
-    http::{APIPaths, ReplaceString, http_get},
-    utils::{combine_page_path, create_auth_header, generate_session_expire_error, combine_path, buffer_to_base64, http_get_err_handle, html_to_text}
+    types::{APIResponseJSON, HTTPResponse, UserData, AuthToken, UserDataValues, UserCollect, UserProfile, UserTypedData, UserTypedCollect, UserDataResponse},
+    http::{APIPaths, ReplaceString},
+    config::read_config,
+    mask::mask_user_datas,
+    utils::{combine_page_path, create_auth_header, generate_session_expire_error, combine_path, buffer_to_base64, http_get_err_handle, html_to_text, generate_parse_error}
 };
 
 lazy_static! {
@@ -17,43 +22,47 @@ lazy_static! {
     static ref FELIDS_SELECTOR: Selector = Selector::parse("td").unwrap();
 }
 
+const BIRTHDAY_LABELS: [&str; 2] = ["出生日期", "生日"];
+const ID_NUMBER_LABELS: [&str; 4] = ["身分證字號", "身分證號", "身份證字號", "身份證號"];
+const ADDRESS_LABELS: [&str; 4] = ["戶籍地址", "通訊地址", "地址", "住址"];
+const GUARDIAN_LABELS: [&str; 3] = ["監護人", "監護人姓名", "家長姓名"];
+const ENROLLMENT_DATE_LABELS: [&str; 2] = ["入學日期", "入學年月"];
+
 const BASE64_IMAGE_HEAD: &str = "data:image/png;base64,";
 const API_PATH: &str = "/v1/login";
 
-async fn fetch_image(host: &str, cookie: &str, id: &str) -> String {
+pub async fn fetch_image(api: &str, host: &str, cookie: &str, id: &str) -> HTTPResponse<Vec<u8>> {
     let page = combine_path(host, &APIPaths::ProfileImage.replace(vec![ReplaceString {
         match_string: "$imgid$".to_owned(),
         replacement: id.to_owned()
     }]));
 
-    let data = http_get(&page, Some(create_auth_header(cookie))).await.unwrap();
-    let buffer = data.bytes().await.unwrap();
+    let data = http_get_err_handle(api, &page, Some(create_auth_header(cookie))).await?;
 
-    buffer_to_base64(&buffer)
+    data.bytes().await.map(|b| b.to_vec()).map_err(|_| generate_parse_error(api, "Profile image"))
 }
 
-async fn get_image(token: AuthToken, document: &str) -> String {
+pub fn get_image_id(api: &str, document: &str) -> HTTPResponse<String> {
     let image_path = {
         let doc = Html::parse_document(document);
-        let image_select = doc.select(&PROFILE_IMAGE_SELECTOR).next().unwrap();
-
-        image_select
-            .value()
-            .attr("src")
-            .unwrap()
-            .to_string()
-            .replace("../", "")
+
+        doc.select(&PROFILE_IMAGE_SELECTOR)
+            .next()
+            .and_then(|image| image.value().attr("src"))
+            .map(|src| src.replace("../", ""))
     };
-    let url = Url::parse(&format!("http://example.com/{}", image_path)).unwrap();
-    let image_url = url
-        .query_pairs()
-        .find(|(key, _)| key == "id")
-        .unwrap().1;
-    let image = image_url.to_string();
 
-    let base64 = fetch_image(&token.host, &token.cookie, &image).await;
+    image_path
+        .and_then(|path| Url::parse(&format!("http://example.com/{}", path)).ok())
+        .and_then(|url| url.query_pairs().find(|(key, _)| key == "id").map(|(_, id)| id.to_string()))
+        .ok_or_else(|| generate_parse_error(api, "Profile image"))
+}
+
+async fn get_image(api: &str, token: &AuthToken, document: &str) -> HTTPResponse<String> {
+    let image = get_image_id(api, document)?;
+    let buffer = fetch_image(api, &token.host, &token.cookie, &image).await?;
 
-    format!("{}{}", BASE64_IMAGE_HEAD, base64)
+    Ok(format!("{}{}", BASE64_IMAGE_HEAD, buffer_to_base64(&buffer)))
 }
 
 async fn get_datas(document: &str) -> Vec<UserDataValues> {
@@ -90,26 +99,91 @@ async fn get_datas(document: &str) -> Vec<UserDataValues> {
     vector
 }
 
-#[get("/getUserInfo")]
-pub async fn api(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<UserData> {
-    let token = auth.0;
+fn set_field(field: &mut Option<String>, extra: &mut BTreeMap<String, String>, data: UserDataValues) {
+    if field.is_none() {
+        *field = Some(data.value);
+    } else {
+        extra.insert(data.name, data.value);
+    }
+}
+
+// The values are masked already by `get_profile`, so the typed fields follow `mask_fields` too.
+pub fn to_user_profile(datas: Vec<UserDataValues>) -> UserProfile {
+    let mut profile = UserProfile::default();
+
+    for data in datas {
+        let name = data.name.as_str();
+
+        if BIRTHDAY_LABELS.contains(&name) {
+            set_field(&mut profile.birthday, &mut profile.extra, data);
+        } else if ID_NUMBER_LABELS.contains(&name) {
+            set_field(&mut profile.idNumber, &mut profile.extra, data);
+        } else if ADDRESS_LABELS.contains(&name) {
+            set_field(&mut profile.address, &mut profile.extra, data);
+        } else if GUARDIAN_LABELS.contains(&name) {
+            set_field(&mut profile.guardian, &mut profile.extra, data);
+        } else if ENROLLMENT_DATE_LABELS.contains(&name) {
+            set_field(&mut profile.enrollmentDate, &mut profile.extra, data);
+        } else if !data.name.is_empty() {
+            profile.extra.insert(data.name, data.value);
+        }
+    }
+
+    profile
+}
 
+// Profile fields and the profile image as a data url.
+// Sensitive fields are masked by `mask_fields` in config, unless `unmask` is set.
+pub async fn get_profile(api: &str, token: AuthToken, unmask: bool) -> HTTPResponse<(Vec<UserDataValues>, String)> {
     let page = combine_page_path(&token.host, APIPaths::Profile);
 
-    let data = http_get_err_handle(API_PATH, &page, Some(create_auth_header(&token.cookie))).await?;
+    let data = http_get_err_handle(api, &page, Some(create_auth_header(&token.cookie))).await?;
 
     if !data.status().is_success() {
-        return Err(generate_session_expire_error(API_PATH))
+        return Err(generate_session_expire_error(api))
     }
 
-    let raw = data.text().await.unwrap();
-    let (image_data, profile_data) = join!(get_image(token, &raw), get_datas(&raw));
+    let raw = data.text().await.map_err(|_| generate_parse_error(api, "Profile"))?;
+    let (image_data, profile_data) = join!(get_image(api, &token, &raw), get_datas(&raw));
+    let image_data = image_data?;
+
+    let profile_data = if unmask { profile_data } else { mask_user_datas(profile_data, &read_config().mask_fields) };
+
+    Ok((profile_data, image_data))
+}
+
+// Sensitive fields are masked by `mask_fields` in config, unless the owner asks for `unmask=true`.
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getUserInfo",
+    tag = "User",
+    responses(
+        (status = 200, description = "Profile, typed when `typed` is set", body = UserDataResponse)
+    ),
+    security(("authToken" = []))
+)]
+#[get("/getUserInfo?<typed>&<unmask>")]
+pub async fn api(auth: AuthorizationToken<AuthToken>, typed: Option<bool>, unmask: Option<bool>) -> APIResponseJSON<UserDataResponse> {
+    let token = auth.0;
 
-    Ok(Custom(Status::Ok, Json(UserData {
-        message: "Get user profile successful".to_owned(),
+    let unmask = unmask.unwrap_or(false);
+    let (profile_data, image_data) = get_profile(API_PATH, token, unmask).await?;
+
+    if typed.unwrap_or(false) {
+        return Ok(Custom(Status::Ok, Json(UserDataResponse::Typed(UserTypedData {
+            message: Message::GetUserProfile,
+            data: UserTypedCollect {
+                data: to_user_profile(profile_data),
+                profileImg: image_data
+            }
+        }))))
+    }
+
+    Ok(Custom(Status::Ok, Json(UserDataResponse::Raw(UserData {
+        message: Message::GetUserProfile,
         data: UserCollect {
             data: profile_data,
             profileImg: image_data
         }
-    })))
-}
\ No newline at end of file
+    }))))
+}
@@ -1,27 +1,38 @@
+use std::{collections::HashMap, sync::Mutex, net::IpAddr};
 use lazy_static::lazy_static;
-use rocket::{http::Status, response::status::Custom, serde::json::Json};
+use rocket::{http::Status, response::status::Custom};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::{
-    types::{APIResponseJSON, Login, ErrorReturn, AuthToken, LoginInfoAuthToken},
-    request_handler::{IncomingDataWrapper, decode_incoming, IncomingError, AuthorizationToken},
-    utils::{self, create_auth_header, get_timestamp, get_time_after, generate_http_error},
+    responder::Json,
+    i18n::Message,
+    types::{APIResponseJSON, HTTPResponse, Login, ErrorReturn, AuthToken, LoginInfoAuthToken},
+    request_handler::{IncomingDataWrapper, decode_incoming, IncomingError, AuthorizationToken, ClientIp},
+    utils::{self, create_auth_header, get_timestamp, get_timestamp_millisec, get_time_after, generate_http_error},
     secure::sign_jwt,
     http::{http_post, APIPaths},
     apis::v1::get_user_info_short::get_user_info_short,
-    config::read_config
+    config::read_config,
+    error::ErrorCode
 };
 
 lazy_static! {
     static ref EXPIRED_TIME: u64 = read_config().login_status_expired.into();
+    // "username@host" or "ip address" to (failed times, timestamp of the first failure)
+    static ref FAILED_RECORD: Mutex<HashMap<String, (u8, u128)>> = Mutex::new(HashMap::new());
 }
 
+const CAPTCHA_KEYWORDS: [&str; 2] = ["驗證碼", "檢查碼"];
+const LOCKED_KEYWORDS: [&str; 4] = ["停用", "鎖定", "凍結", "禁止登入"];
+const CREDENTIAL_KEYWORDS: [&str; 2] = ["密碼", "帳號"];
+
 const API_PATH: &str = "/v1/login";
-fn error_message(code: Status, message: &str, at: Option<&str>) -> ErrorReturn {
-    utils::error_message(API_PATH, code, message, at)
+fn error_message(status: Status, code: ErrorCode, message: Message, at: Option<&str>) -> ErrorReturn {
+    utils::error_message(API_PATH, status, code, message, at)
 }
 
-#[derive(Debug, FromForm, Deserialize)]
+#[derive(Debug, FromForm, Deserialize, ToSchema)]
 pub struct IncomingData {
     username: String,
     password: String,
@@ -39,40 +50,115 @@ struct DataPOST {
     vcode: String
 }
 
-#[post("/login", data = "<data>")]
-pub async fn api(auth: AuthorizationToken<LoginInfoAuthToken>, data: Result<IncomingDataWrapper<IncomingData>, IncomingError>) -> APIResponseJSON<Login> {
-    let token = auth.0.clone();
-    let data = match data {
-        Ok(d) => decode_incoming(d),
-        Err(err) => match err {
-            IncomingError::MissingArguments(args) => {
-                return Err(error_message(Status::BadRequest, "Argument is not satisfied", Some(&format!("Argument: {}", args.join(", ")))))
-            },
-            _ => return Err(error_message(Status::BadRequest, "Content-Type is not provided", Some("Header: Content-Type")))
+#[derive(Debug, PartialEq)]
+pub enum LoginFailure {
+    CaptchaWrong,
+    CredentialsWrong,
+    AccountLocked,
+    Unknown
+}
+
+// The login page shows the reason with `alert('...')` when the login is not redirected.
+// The message ends at its closing quote, since it may contain brackets like "（錯誤次數：1)".
+pub fn get_alert_message(body: &str) -> Option<&str> {
+    let start = body.find("alert(")? + "alert(".len();
+    let rest = body[start..].trim_start();
+    let quote = rest.chars().next().filter(|c| *c == '\'' || *c == '"')?;
+    let rest = &rest[1..];
+
+    let mut escaped = false;
+    for (i, c) in rest.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            _ if c == quote => return Some(rest[..i].trim()),
+            _ => {}
         }
+    }
+
+    None
+}
+
+// Only the alert is read, the rest of the page has the labels of the form like "帳號" and "密碼".
+pub fn detect_login_failure(body: &str) -> LoginFailure {
+    let message = match get_alert_message(body) {
+        Some(message) => message,
+        None => return LoginFailure::Unknown
     };
 
+    if CAPTCHA_KEYWORDS.iter().any(|k| message.contains(k)) {
+        LoginFailure::CaptchaWrong
+    } else if LOCKED_KEYWORDS.iter().any(|k| message.contains(k)) {
+        LoginFailure::AccountLocked
+    } else if CREDENTIAL_KEYWORDS.iter().any(|k| message.contains(k)) {
+        LoginFailure::CredentialsWrong
+    } else {
+        LoginFailure::Unknown
+    }
+}
+
+fn is_locked(key: &str, limit: u8) -> bool {
+    let config = read_config();
+
+    match FAILED_RECORD.lock().unwrap().get(key) {
+        Some((times, first)) => *times >= limit && get_timestamp_millisec() < first + config.failed_expried as u128,
+        None => false
+    }
+}
+
+// Captcha typos are not counted, the counter is reset after `failed_expried` milliseconds from the first failure.
+fn record_failure(key: &str) {
+    let config = read_config();
+    let now = get_timestamp_millisec();
+    let mut record = FAILED_RECORD.lock().unwrap();
+
+    // Expired counters are dropped here, so the map does not grow with every name that was ever tried.
+    record.retain(|_, (_, first)| now < *first + config.failed_expried as u128);
+
+    let entry = record.entry(key.to_owned()).or_insert((0, now));
+
+    if now >= entry.1 + config.failed_expried as u128 {
+        *entry = (0, now);
+    }
+
+    entry.0 = entry.0.saturating_add(1);
+}
+
+// Returns the signed auth token of the user.
+// The failures are counted per account, and per client IP with a higher limit so one client cannot try many accounts.
+pub async fn login(api: &str, token: LoginInfoAuthToken, client_ip: Option<IpAddr>, username: String, password: String, vcode: String) -> HTTPResponse<String> {
+    let config = read_config();
+    let failed_key = format!("{}@{}", username, token.host);
+    let ip_key = client_ip.map(|ip| ip.to_string());
+
+    let ip_locked = ip_key.as_ref().is_some_and(|key| is_locked(key, config.failed_times_lock_ip));
+    if is_locked(&failed_key, config.failed_times_lock) || ip_locked {
+        return Err(utils::error_message(api, Status::TooManyRequests, ErrorCode::TooManyAttempts, Message::TooManyAttempts, None))
+    }
+
     let page = utils::combine_page_path(&token.host, APIPaths::Login);
     let form = DataPOST {
-        __RequestVerificationToken: token.site_key,
+        __RequestVerificationToken: token.site_key.clone(),
         division: "senior".to_owned(),
-        Loginid: data.username,
-        LoginPwd: data.password,
+        Loginid: username,
+        LoginPwd: password,
         Uid: "".to_owned(),
-        vcode: data.vcode
+        vcode
     };
 
     let request = match http_post(&page, form, Some(create_auth_header(&token.cookie))).await {
         Ok(response) => response,
-        Err(err) => return Err(generate_http_error(API_PATH, err))
+        Err(err) => return Err(generate_http_error(api, err))
     };
 
     let is_redict = request.status().is_redirection();
 
     if is_redict {
-        let user_data = get_user_info_short(auth.0.clone()).await;
+        let user_data = get_user_info_short(token.clone()).await;
 
         if let Ok(data) = user_data {
+            FAILED_RECORD.lock().unwrap().remove(&failed_key);
+
             let token = sign_jwt(&AuthToken {
                 host: token.host,
                 cookie: token.cookie,
@@ -82,12 +168,56 @@ pub async fn api(auth: AuthorizationToken<LoginInfoAuthToken>, data: Result<Inco
                 exp: get_time_after(*EXPIRED_TIME)
             }).unwrap();
 
-            return Ok(Custom(Status::Ok, Json(Login {
-                message: "Login successful!".to_owned(),
-                authtoken: token
-            })))
+            return Ok(token)
+        }
+
+        // Logged in, but the user data page cannot be read.
+        return Err(utils::error_message(api, Status::Forbidden, ErrorCode::ParseFailed, Message::LoginFailed, Some("Fetching user data")))
+    }
+
+    let failure = detect_login_failure(&request.text().await.unwrap_or_default());
+    if failure != LoginFailure::CaptchaWrong {
+        record_failure(&failed_key);
+        if let Some(key) = &ip_key {
+            record_failure(key);
         }
     }
 
-    Err(error_message(Status::Forbidden, "Login failed", None))
-}
\ No newline at end of file
+    Err(match failure {
+        LoginFailure::CaptchaWrong => utils::error_message(api, Status::Forbidden, ErrorCode::CaptchaWrong, Message::CaptchaWrong, Some("Argument: vcode")),
+        LoginFailure::CredentialsWrong => utils::error_message(api, Status::Forbidden, ErrorCode::CredentialsWrong, Message::CredentialsWrong, Some("Argument: username, password")),
+        LoginFailure::AccountLocked => utils::error_message(api, Status::Forbidden, ErrorCode::AccountLocked, Message::AccountDisabled, None),
+        LoginFailure::Unknown => utils::error_message(api, Status::Forbidden, ErrorCode::LoginFailed, Message::LoginFailed, None)
+    })
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "login",
+    tag = "Login",
+    request_body(content = inline(IncomingData), content_type = "application/json"),
+    responses(
+        (status = 200, description = "Auth token of the user", body = Login)
+    ),
+    security(("loginInfoToken" = []))
+)]
+#[post("/login", data = "<data>")]
+pub async fn api(auth: AuthorizationToken<LoginInfoAuthToken>, client_ip: ClientIp, data: Result<IncomingDataWrapper<IncomingData>, IncomingError>) -> APIResponseJSON<Login> {
+    let token = auth.0;
+    let data = match data {
+        Ok(d) => decode_incoming(d),
+        Err(err) => match err {
+            IncomingError::MissingArguments(args) => {
+                return Err(error_message(Status::BadRequest, ErrorCode::MissingArgument, Message::ArgumentNotSatisfied, Some(&format!("Argument: {}", args.join(", ")))))
+            },
+            _ => return Err(error_message(Status::BadRequest, ErrorCode::UnsupportedMediaType, Message::ContentTypeMissing, Some("Header: Content-Type")))
+        }
+    };
+
+    let authtoken = login(API_PATH, token, client_ip.0, data.username, data.password, data.vcode).await?;
+
+    Ok(Custom(Status::Ok, Json(Login {
+        message: Message::LoginSuccessful,
+        authtoken
+    })))
+}
@@ -1,14 +1,29 @@
 use rocket::{Rocket, Build};
 
-mod get_login_info;
-mod get_login_captcha;
-mod login;
+pub mod get_login_info;
+pub mod get_login_captcha;
+pub mod login;
 mod get_user_info_short;
-mod get_user_profile;
-mod get_available_score;
-mod get_reward_and_punish;
-mod get_score;
-mod get_lack;
+pub mod get_user_profile;
+mod get_profile_image;
+pub mod get_available_score;
+pub mod get_reward_and_punish;
+pub mod get_score;
+pub mod get_all_scores;
+pub mod get_lack;
+pub mod get_score_analytics;
+pub mod get_lack_analytics;
+mod get_merit_balance;
+pub mod get_schedule;
+mod get_schedule_image;
+mod calendar_feed;
+mod watch_score;
+mod push_subscription;
+mod webhook;
+mod export;
+mod get_transcript;
+mod batch;
+pub mod openapi;
 
 pub fn init_v1_api(server: Rocket<Build>) -> Rocket<Build> {
     server.mount("/v1", routes![
@@ -20,9 +35,48 @@ pub fn init_v1_api(server: Rocket<Build>) -> Rocket<Build> {
         // User data
         get_user_info_short::api,
         get_user_profile::api,
+        get_profile_image::api,
         get_available_score::api,
         get_reward_and_punish::api,
         get_score::api,
-        get_lack::api
+        get_all_scores::api,
+        get_lack::api,
+        get_schedule::api,
+        get_schedule::api_list,
+        get_schedule_image::api,
+        batch::api,
+
+        // Analytics
+        get_score_analytics::api,
+        get_lack_analytics::api,
+        get_merit_balance::api,
+
+        // Export
+        export::api_score,
+        export::api_all_scores,
+        export::api_lack,
+        export::api_reward_and_punish,
+        get_transcript::api,
+
+        // Calendar
+        calendar_feed::api,
+        calendar_feed::api_delete,
+        calendar_feed::api_schedule,
+        calendar_feed::api_lack,
+
+        // Notification
+        watch_score::api,
+        watch_score::api_delete,
+        push_subscription::api_public_key,
+        push_subscription::api,
+        push_subscription::api_list,
+        push_subscription::api_delete,
+        webhook::api,
+        webhook::api_list,
+        webhook::api_delete,
+
+        // Documentation
+        openapi::api,
+        openapi::api_docs
     ])
 }
\ No newline at end of file
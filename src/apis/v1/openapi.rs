@@ -0,0 +1,149 @@
+use rocket::{serde::json::Json, response::content::RawHtml};
+use utoipa::{
+    Modify, OpenApi,
+    openapi::{
+        self, Content, Ref, RefOr, ResponseBuilder,
+        security::{HttpAuthScheme, HttpBuilder, SecurityScheme}
+    }
+};
+
+use crate::{types, error::ErrorCode, timetable::{TimetableTheme, TimetableDevice}, export::ExportFormat};
+use super::{
+    get_login_info, get_login_captcha, login, get_user_info_short, get_user_profile, get_profile_image, get_available_score,
+    get_reward_and_punish, get_score, get_all_scores, get_lack, get_schedule, get_schedule_image, get_score_analytics, get_lack_analytics,
+    get_merit_balance, export, get_transcript, batch, calendar_feed, watch_score, push_subscription, webhook
+};
+
+// Paths, parameters and methods are read from the rocket attributes, `tests/openapi.rs` checks the document against the mounted routes.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "HLHSInfo Backend Server"),
+    paths(
+        // Login data
+        get_login_info::api,
+        get_login_captcha::api,
+        login::api,
+
+        // User data
+        get_user_info_short::api,
+        get_user_profile::api,
+        get_profile_image::api,
+        get_available_score::api,
+        get_reward_and_punish::api,
+        get_score::api,
+        get_all_scores::api,
+        get_lack::api,
+        get_schedule::api,
+        get_schedule::api_list,
+        get_schedule_image::api,
+        batch::api,
+
+        // Analytics
+        get_score_analytics::api,
+        get_lack_analytics::api,
+        get_merit_balance::api,
+
+        // Export
+        export::api_score,
+        export::api_all_scores,
+        export::api_lack,
+        export::api_reward_and_punish,
+        get_transcript::api,
+
+        // Calendar
+        calendar_feed::api,
+        calendar_feed::api_delete,
+        calendar_feed::api_schedule,
+        calendar_feed::api_lack,
+
+        // Notification
+        watch_score::api,
+        watch_score::api_delete,
+        push_subscription::api_public_key,
+        push_subscription::api,
+        push_subscription::api_list,
+        push_subscription::api_delete,
+        webhook::api,
+        webhook::api_list,
+        webhook::api_delete
+    ),
+    components(schemas(
+        ErrorCode, TimetableTheme, TimetableDevice, ExportFormat, batch::BatchSection,
+        types::ResponseErrorAt, types::ErrorResponse,
+        types::LoginInfo, types::Login,
+        types::UserDataValues, types::UserCollect, types::UserData, types::UserProfile, types::UserTypedCollect, types::UserTypedData, types::UserDataResponse,
+        types::UserProfileShortValue, types::UserProfileShortData,
+        types::AvailableScoreValue, types::AvailableScoreData,
+        types::ScoreValue, types::ScoreDataValue, types::ScoreExtraData, types::ScoreUnpass, types::ScoreDataCollect, types::ScoreData,
+        types::AllScoreNormalDataValue, types::AllScoreNormalData, types::AllScoreTestDataInfo, types::AllScoreTestDataValue, types::AllScoreTestData, types::AllScoreTestCollect, types::AllScoreData,
+        types::ScoreAnalyticsTest, types::ScoreTrendPoint, types::ScoreSubjectTrend, types::ScoreAnalyticsCollect, types::ScoreAnalyticsData,
+        types::WatchScoreCollect, types::WatchScoreData,
+        types::PushPublicKeyCollect, types::PushPublicKeyData,
+        types::PushSubscriptionKeys, types::PushSubscriptionValue, types::PushSubscriptionData, types::PushSubscriptionListData,
+        types::WebhookValue, types::WebhookCreateCollect, types::WebhookCreateData, types::WebhookListData,
+        types::RewardAndPunishDetailValue, types::RewardAndPunishStatus, types::RewardAndPunishCollect, types::RewardAndPunishData,
+        types::MeritBalanceCollect, types::MeritBalanceData,
+        types::LackRecordValue, types::LackStatusValue, types::LackStatus, types::LackCollect, types::LackData,
+        types::Weekday, types::LackPeriod, types::AbsenceKind, types::LackTypedRecord, types::LackTypedCollect, types::LackTypedData, types::LackDataResponse,
+        types::LackCategoryCount, types::LackPeriodCount, types::LackMonthCount, types::LackRuleLevel, types::LackRuleStatus, types::LackAnalyticsCollect, types::LackAnalyticsData,
+        types::ScheduleListValues, types::ScheduleListCollect, types::ScheduleListData,
+        types::ScheduleValue, types::ScheduleCollect, types::ScheduleData,
+        types::BatchCollect, types::BatchData,
+        types::CalendarFeedCollect, types::CalendarFeedData
+    )),
+    tags(
+        (name = "Login"),
+        (name = "User"),
+        (name = "Analytics"),
+        (name = "Export"),
+        (name = "Calendar"),
+        (name = "Notification")
+    ),
+    modifiers(&ApiAddon)
+)]
+pub struct ApiDoc;
+
+struct ApiAddon;
+
+impl Modify for ApiAddon {
+    fn modify(&self, openapi: &mut openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme("loginInfoToken", SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .description(Some("`authtoken` of /v1/getLoginInfo"))
+                    .build()
+            ));
+            components.add_security_scheme("authToken", SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .description(Some("`authtoken` of /v1/login"))
+                    .build()
+            ));
+        }
+
+        // Every api answers `ErrorResponse` when it fails.
+        for path in openapi.paths.paths.values_mut() {
+            for operation in path.operations.values_mut() {
+                operation.responses.responses.insert("default".to_owned(), RefOr::T(
+                    ResponseBuilder::new()
+                        .description("Error, see `code` for the reason")
+                        .content("application/json", Content::new(Ref::from_schema_name("ErrorResponse")))
+                        .build()
+                ));
+            }
+        }
+    }
+}
+
+#[get("/openapi.json")]
+pub fn api() -> Json<openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+#[get("/docs")]
+pub fn api_docs() -> RawHtml<&'static str> {
+    RawHtml(include_str!("openapi.html"))
+}
@@ -0,0 +1,125 @@
+use rocket::{http::Status, response::status::Custom};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    responder::Json,
+    i18n::Message,
+    request_handler::{AuthorizationToken, IncomingDataWrapper, IncomingError, decode_incoming},
+    types::{AuthToken, APIResponseJSON, ErrorReturn, PushSubscriptionKeys, PushSubscriptionData, PushSubscriptionListData, PushPublicKeyData, PushPublicKeyCollect},
+    push::{add_subscription, list_subscriptions, remove_subscription, get_vapid_public_key},
+    utils::{self, base64_url_to_buffer, is_public_https_url, user_key},
+    error::ErrorCode
+};
+
+const API_PATH: &str = "/v1/pushSubscription";
+fn error_message(status: Status, code: ErrorCode, message: Message, at: Option<&str>) -> ErrorReturn {
+    utils::error_message(API_PATH, status, code, message, at)
+}
+
+#[derive(Debug, FromForm, Deserialize, ToSchema)]
+pub struct IncomingData {
+    endpoint: String,
+    keys: PushSubscriptionKeys
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "getPushPublicKey",
+    tag = "Notification",
+    responses(
+        (status = 200, description = "VAPID public key", body = PushPublicKeyData)
+    )
+)]
+#[get("/getPushPublicKey")]
+pub async fn api_public_key() -> APIResponseJSON<PushPublicKeyData> {
+    let public_key = match get_vapid_public_key() {
+        Ok(key) => key,
+        Err(_) => return Err(error_message(Status::ServiceUnavailable, ErrorCode::FeatureDisabled, Message::PushUnavailable, None))
+    };
+
+    Ok(Custom(Status::Ok, Json(PushPublicKeyData {
+        message: Message::GetPushPublicKey,
+        data: PushPublicKeyCollect {
+            publicKey: public_key
+        }
+    })))
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "addPushSubscription",
+    tag = "Notification",
+    request_body(content = inline(IncomingData), content_type = "application/json"),
+    responses(
+        (status = 200, description = "Created subscription", body = PushSubscriptionData)
+    ),
+    security(("authToken" = []))
+)]
+#[post("/pushSubscription", data = "<data>")]
+pub async fn api(auth: AuthorizationToken<AuthToken>, data: Result<IncomingDataWrapper<IncomingData>, IncomingError>) -> APIResponseJSON<PushSubscriptionData> {
+    let data = match data {
+        Ok(d) => decode_incoming(d),
+        Err(err) => match err {
+            IncomingError::MissingArguments(args) => {
+                return Err(error_message(Status::BadRequest, ErrorCode::MissingArgument, Message::ArgumentNotSatisfied, Some(&format!("Argument: {}", args.join(", ")))))
+            },
+            _ => return Err(error_message(Status::BadRequest, ErrorCode::UnsupportedMediaType, Message::ContentTypeMissing, Some("Header: Content-Type")))
+        }
+    };
+
+    if !is_public_https_url(&data.endpoint).await {
+        return Err(error_message(Status::BadRequest, ErrorCode::InvalidArgument, Message::WrongArguments, Some("Argument: endpoint")))
+    }
+
+    if base64_url_to_buffer(&data.keys.p256dh).is_none() || base64_url_to_buffer(&data.keys.auth).is_none() {
+        return Err(error_message(Status::BadRequest, ErrorCode::InvalidArgument, Message::WrongArguments, Some("Argument: keys")))
+    }
+
+    let subscription = add_subscription(&user_key(&auth.0.user_data.schoolNumber, &auth.0.host), &data.endpoint, data.keys);
+
+    Ok(Custom(Status::Ok, Json(PushSubscriptionData {
+        message: Message::AddPushSubscription,
+        data: subscription
+    })))
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "listPushSubscriptions",
+    tag = "Notification",
+    responses(
+        (status = 200, description = "Subscriptions of the user", body = PushSubscriptionListData)
+    ),
+    security(("authToken" = []))
+)]
+#[get("/pushSubscription")]
+pub async fn api_list(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<PushSubscriptionListData> {
+    Ok(Custom(Status::Ok, Json(PushSubscriptionListData {
+        message: Message::GetPushSubscription,
+        data: list_subscriptions(&user_key(&auth.0.user_data.schoolNumber, &auth.0.host))
+    })))
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "deletePushSubscription",
+    tag = "Notification",
+    responses(
+        (status = 200, description = "Remaining subscriptions", body = PushSubscriptionListData)
+    ),
+    security(("authToken" = []))
+)]
+#[delete("/pushSubscription/<id>")]
+pub async fn api_delete(auth: AuthorizationToken<AuthToken>, id: &str) -> APIResponseJSON<PushSubscriptionListData> {
+    let key = user_key(&auth.0.user_data.schoolNumber, &auth.0.host);
+
+    if !remove_subscription(&key, id) {
+        return Err(error_message(Status::NotFound, ErrorCode::NotFound, Message::PushSubscriptionNotFound, Some("Argument: id")))
+    }
+
+    Ok(Custom(Status::Ok, Json(PushSubscriptionListData {
+        message: Message::DeletePushSubscription,
+        data: list_subscriptions(&key)
+    })))
+}
\ No newline at end of file
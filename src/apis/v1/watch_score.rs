@@ -0,0 +1,89 @@
+use rocket::{http::Status, response::status::Custom};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    responder::Json,
+    i18n::Message,
+    request_handler::{AuthorizationToken, IncomingDataWrapper, IncomingError, decode_incoming},
+    types::{AuthToken, APIResponseJSON, WatchScoreData, WatchScoreCollect, ErrorReturn},
+    watcher::{watch, unwatch},
+    config::read_config,
+    utils::{self, is_public_https_url},
+    error::ErrorCode
+};
+
+const API_PATH: &str = "/v1/watchScore";
+fn error_message(status: Status, code: ErrorCode, message: Message, at: Option<&str>) -> ErrorReturn {
+    utils::error_message(API_PATH, status, code, message, at)
+}
+
+#[derive(Debug, FromForm, Deserialize, ToSchema)]
+pub struct IncomingData {
+    webhook: Option<String>
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "watchScore",
+    tag = "Notification",
+    request_body(content = inline(IncomingData), content_type = "application/json"),
+    responses(
+        (status = 200, description = "Watching status", body = WatchScoreData)
+    ),
+    security(("authToken" = []))
+)]
+#[post("/watchScore", data = "<data>")]
+pub async fn api(auth: AuthorizationToken<AuthToken>, data: Result<IncomingDataWrapper<IncomingData>, IncomingError>) -> APIResponseJSON<WatchScoreData> {
+    let token = auth.0;
+
+    if !read_config().enable_record {
+        return Err(error_message(Status::Forbidden, ErrorCode::FeatureDisabled, Message::ScoreWatchingDisabled, None))
+    }
+
+    let webhook = match data {
+        Ok(d) => decode_incoming(d).webhook.filter(|w| !w.is_empty()),
+        Err(_) => None
+    };
+
+    if let Some(url) = &webhook {
+        if !is_public_https_url(url).await {
+            return Err(error_message(Status::BadRequest, ErrorCode::InvalidArgument, Message::WrongArguments, Some("Argument: webhook")))
+        }
+    }
+
+    let expired = token.exp;
+    watch(token, webhook.clone());
+
+    Ok(Custom(Status::Ok, Json(WatchScoreData {
+        message: Message::WatchScore,
+        data: WatchScoreCollect {
+            webhook,
+            expiredTimestamp: expired
+        }
+    })))
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "unwatchScore",
+    tag = "Notification",
+    responses(
+        (status = 200, description = "Watching status", body = WatchScoreData)
+    ),
+    security(("authToken" = []))
+)]
+#[delete("/watchScore")]
+pub async fn api_delete(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<WatchScoreData> {
+    if !unwatch(&auth.0) {
+        return Err(error_message(Status::NotFound, ErrorCode::NotFound, Message::NotWatchingScore, None))
+    }
+
+    Ok(Custom(Status::Ok, Json(WatchScoreData {
+        message: Message::UnwatchScore,
+        data: WatchScoreCollect {
+            webhook: None,
+            expiredTimestamp: auth.0.exp
+        }
+    })))
+}
\ No newline at end of file
@@ -0,0 +1,107 @@
+use rocket::{http::Status, response::status::Custom};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    responder::Json,
+    i18n::Message,
+    request_handler::{AuthorizationToken, IncomingDataWrapper, IncomingError, decode_incoming},
+    types::{AuthToken, APIResponseJSON, ErrorReturn, WebhookCreateData, WebhookCreateCollect, WebhookListData},
+    webhook::{add_webhook, list_webhooks, remove_webhook},
+    notify::EVENTS,
+    utils::{self, is_public_https_url, user_key},
+    error::ErrorCode
+};
+
+const API_PATH: &str = "/v1/webhook";
+fn error_message(status: Status, code: ErrorCode, message: Message, at: Option<&str>) -> ErrorReturn {
+    utils::error_message(API_PATH, status, code, message, at)
+}
+
+#[derive(Debug, FromForm, Deserialize, ToSchema)]
+pub struct IncomingData {
+    url: String,
+    events: Vec<String>
+}
+
+// The secret is only returned once, it is used to verify `X-HLHSInfo-Signature` of every delivery.
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "addWebhook",
+    tag = "Notification",
+    request_body(content = inline(IncomingData), content_type = "application/json"),
+    responses(
+        (status = 200, description = "Created webhook with its secret", body = WebhookCreateData)
+    ),
+    security(("authToken" = []))
+)]
+#[post("/webhook", data = "<data>")]
+pub async fn api(auth: AuthorizationToken<AuthToken>, data: Result<IncomingDataWrapper<IncomingData>, IncomingError>) -> APIResponseJSON<WebhookCreateData> {
+    let data = match data {
+        Ok(d) => decode_incoming(d),
+        Err(err) => match err {
+            IncomingError::MissingArguments(args) => {
+                return Err(error_message(Status::BadRequest, ErrorCode::MissingArgument, Message::ArgumentNotSatisfied, Some(&format!("Argument: {}", args.join(", ")))))
+            },
+            _ => return Err(error_message(Status::BadRequest, ErrorCode::UnsupportedMediaType, Message::ContentTypeMissing, Some("Header: Content-Type")))
+        }
+    };
+
+    if !is_public_https_url(&data.url).await {
+        return Err(error_message(Status::BadRequest, ErrorCode::InvalidArgument, Message::WrongArguments, Some("Argument: url")))
+    }
+
+    if data.events.is_empty() || data.events.iter().any(|e| !EVENTS.contains(&e.as_str())) {
+        return Err(error_message(Status::BadRequest, ErrorCode::InvalidArgument, Message::WrongArguments, Some("Argument: events")))
+    }
+
+    let (webhook, secret) = add_webhook(&user_key(&auth.0.user_data.schoolNumber, &auth.0.host), &data.url, data.events);
+
+    Ok(Custom(Status::Ok, Json(WebhookCreateData {
+        message: Message::AddWebhook,
+        data: WebhookCreateCollect {
+            webhook,
+            secret
+        }
+    })))
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "listWebhooks",
+    tag = "Notification",
+    responses(
+        (status = 200, description = "Webhooks of the user", body = WebhookListData)
+    ),
+    security(("authToken" = []))
+)]
+#[get("/webhook")]
+pub async fn api_list(auth: AuthorizationToken<AuthToken>) -> APIResponseJSON<WebhookListData> {
+    Ok(Custom(Status::Ok, Json(WebhookListData {
+        message: Message::GetWebhook,
+        data: list_webhooks(&user_key(&auth.0.user_data.schoolNumber, &auth.0.host))
+    })))
+}
+
+#[utoipa::path(
+    context_path = "/v1",
+    operation_id = "deleteWebhook",
+    tag = "Notification",
+    responses(
+        (status = 200, description = "Remaining webhooks", body = WebhookListData)
+    ),
+    security(("authToken" = []))
+)]
+#[delete("/webhook/<id>")]
+pub async fn api_delete(auth: AuthorizationToken<AuthToken>, id: &str) -> APIResponseJSON<WebhookListData> {
+    let key = user_key(&auth.0.user_data.schoolNumber, &auth.0.host);
+
+    if !remove_webhook(&key, id) {
+        return Err(error_message(Status::NotFound, ErrorCode::NotFound, Message::WebhookNotFound, Some("Argument: id")))
+    }
+
+    Ok(Custom(Status::Ok, Json(WebhookListData {
+        message: Message::DeleteWebhook,
+        data: list_webhooks(&key)
+    })))
+}
\ No newline at end of file
@@ -0,0 +1,110 @@
+use rocket::{response::{Responder, status::Custom}, Response, http::{Status, Header}};
+use serde::Serialize;
+
+use crate::{responder::Json, i18n::Message, types::ErrorReturn, error::ErrorCode, utils};
+
+pub const API_VERSION: &str = "2";
+
+// Every v2 response is `{ ok, data, error, meta }`, `data` is null when it fails and `error` is null when it succeeds.
+#[derive(Debug, Serialize)]
+pub struct Envelope<T> {
+    pub ok: bool,
+    pub data: Option<T>,
+    pub error: Option<EnvelopeError>,
+    pub meta: Meta
+}
+
+#[derive(Debug, Serialize)]
+pub struct Meta {
+    pub version: &'static str,
+    pub api: String,
+    pub timestamp: u128
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnvelopeError {
+    pub code: ErrorCode,
+    pub message: Message,
+    pub at: Option<String>
+}
+
+impl Meta {
+    fn new(request: &rocket::Request<'_>) -> Self {
+        Meta {
+            version: API_VERSION,
+            api: request.uri().path().to_string(),
+            timestamp: utils::get_timestamp_millisec()
+        }
+    }
+}
+
+pub struct ApiData<T>(pub T);
+
+pub struct ApiError {
+    pub status: Status,
+    pub error: EnvelopeError,
+    // `WWW-Authenticate` header of a 401 response.
+    pub challenge: Option<String>
+}
+
+pub type ApiResponse<T> = Result<ApiData<T>, ApiError>;
+
+impl ApiError {
+    pub fn new(status: Status, code: ErrorCode, message: Message, at: Option<&str>) -> Self {
+        ApiError {
+            status,
+            error: EnvelopeError {
+                code,
+                message,
+                at: at.map(str::to_owned)
+            },
+            challenge: None
+        }
+    }
+}
+
+// The scrapers are shared with v1, so their errors are converted to the envelope.
+impl From<ErrorReturn> for ApiError {
+    fn from(err: ErrorReturn) -> Self {
+        let Custom(status, Json(body)) = err;
+
+        ApiError {
+            status,
+            error: EnvelopeError {
+                code: body.code,
+                message: body.message,
+                at: body.wrong.and_then(|w| w.at)
+            },
+            challenge: None
+        }
+    }
+}
+
+impl<'r, T: Serialize> Responder<'r, 'static> for ApiData<T> {
+    fn respond_to(self, request: &'r rocket::Request<'_>) -> rocket::response::Result<'static> {
+        Json(Envelope {
+            ok: true,
+            data: Some(self.0),
+            error: None,
+            meta: Meta::new(request)
+        }).respond_to(request)
+    }
+}
+
+impl<'r> Responder<'r, 'static> for ApiError {
+    fn respond_to(self, request: &'r rocket::Request<'_>) -> rocket::response::Result<'static> {
+        let body = Json(Envelope::<()> {
+            ok: false,
+            data: None,
+            error: Some(self.error),
+            meta: Meta::new(request)
+        });
+        let mut response = Response::build_from(Custom(self.status, body).respond_to(request)?);
+
+        if let Some(challenge) = self.challenge {
+            response.header(Header::new("WWW-Authenticate", challenge));
+        }
+
+        response.ok()
+    }
+}
@@ -0,0 +1,68 @@
+use rocket::http::Status;
+
+use crate::{
+    i18n::Message,
+    types::{AuthToken, AvailableScoreValue, ScoreExtraData},
+    request_handler::AuthorizationToken,
+    apis::v1::{get_available_score::get_available_score, get_score::{fetch_score, ScoreRecord}},
+    error::ErrorCode
+};
+use super::{envelope::{ApiData, ApiError, ApiResponse}, types::{Exam, ExamKind, ExamScore, Subject, ExamExtra}};
+
+const API_PATH: &str = "/v2/exams";
+
+impl From<AvailableScoreValue> for Exam {
+    fn from(exam: AvailableScoreValue) -> Self {
+        Exam {
+            id: exam.testID,
+            name: exam.name,
+            year: exam.year as u16,
+            gregorian_year: exam.gregorianYear,
+            term: exam.term,
+            times: exam.times,
+            kind: if exam.r#type == 2 { ExamKind::Regular } else { ExamKind::Exam }
+        }
+    }
+}
+
+pub fn to_exam_score(records: Vec<ScoreRecord>, extra: Vec<ScoreExtraData>) -> ExamScore {
+    ExamScore {
+        subjects: records
+            .into_iter()
+            .map(|r| Subject {
+                score: r.score,
+                gpa: r.gpa,
+                score_failed: r.score_unpass,
+                gpa_failed: r.gpa_unpass,
+                name: r.name
+            })
+            .collect(),
+        extra: extra
+            .into_iter()
+            .map(|e| ExamExtra {
+                name: e.r#type,
+                value: e.value
+            })
+            .collect()
+    }
+}
+
+#[get("/exams")]
+pub async fn api(auth: AuthorizationToken<AuthToken>) -> ApiResponse<Vec<Exam>> {
+    let exams = get_available_score(API_PATH, &auth.0).await?;
+
+    Ok(ApiData(exams.into_iter().map(Exam::from).collect()))
+}
+
+#[get("/exams/<id>?<year>&<term>")]
+pub async fn api_score(auth: AuthorizationToken<AuthToken>, id: &str, year: Option<u16>, term: Option<u8>) -> ApiResponse<ExamScore> {
+    let (year, term) = match (year, term) {
+        (Some(year), Some(term)) => (year, term),
+        _ => return Err(ApiError::new(Status::BadRequest, ErrorCode::MissingArgument, Message::MissingArguments, Some("Argument: year, term")))
+    };
+
+    let api = format!("{}/{}", API_PATH, id);
+    let (records, extra) = fetch_score(&api, &auth.0, &year.to_string(), &term.to_string(), id).await?;
+
+    Ok(ApiData(to_exam_score(records, extra)))
+}
@@ -0,0 +1,64 @@
+use rocket::http::Status;
+use serde::Deserialize;
+
+use crate::{
+    i18n::Message,
+    types::LoginInfoAuthToken,
+    request_handler::{IncomingDataWrapper, decode_incoming, IncomingError, AuthorizationToken, ClientIp},
+    apis::v1::{get_login_info::get_login_info, get_login_captcha::get_login_captcha, login::login},
+    utils::buffer_to_base64,
+    error::ErrorCode
+};
+use super::{envelope::{ApiData, ApiError, ApiResponse}, types::{LoginSession, Captcha, LoginResult}};
+
+const API_PATH: &str = "/v2/login";
+const CAPTCHA_API_PATH: &str = "/v2/login/captcha";
+const CAPTCHA_IMAGE_HEAD: &str = "data:image/gif;base64,";
+
+#[derive(Debug, FromForm, Deserialize)]
+pub struct IncomingData {
+    username: String,
+    password: String,
+    captcha: String
+}
+
+#[get("/login?<host>")]
+pub async fn api_session(host: Option<&str>) -> ApiResponse<LoginSession> {
+    let host = match host {
+        Some(x) => x,
+        None => return Err(ApiError::new(Status::BadRequest, ErrorCode::MissingArgument, Message::MissingArguments, Some("Argument: host")))
+    };
+
+    let info = get_login_info(API_PATH, host).await?;
+
+    Ok(ApiData(LoginSession {
+        token: info.authToken,
+        need_captcha: info.need_captcha
+    }))
+}
+
+#[get("/login/captcha")]
+pub async fn api_captcha(auth: AuthorizationToken<LoginInfoAuthToken>) -> ApiResponse<Captcha> {
+    let captcha = get_login_captcha(CAPTCHA_API_PATH, &auth.0).await?;
+
+    Ok(ApiData(Captcha {
+        image: format!("{}{}", CAPTCHA_IMAGE_HEAD, buffer_to_base64(&captcha))
+    }))
+}
+
+#[post("/login", data = "<data>")]
+pub async fn api(auth: AuthorizationToken<LoginInfoAuthToken>, client_ip: ClientIp, data: Result<IncomingDataWrapper<IncomingData>, IncomingError>) -> ApiResponse<LoginResult> {
+    let data = match data {
+        Ok(d) => decode_incoming(d),
+        Err(IncomingError::MissingArguments(args)) => {
+            return Err(ApiError::new(Status::BadRequest, ErrorCode::MissingArgument, Message::ArgumentNotSatisfied, Some(&format!("Argument: {}", args.join(", ")))))
+        },
+        Err(_) => return Err(ApiError::new(Status::BadRequest, ErrorCode::UnsupportedMediaType, Message::ContentTypeMissing, Some("Header: Content-Type")))
+    };
+
+    let token = login(API_PATH, auth.0, client_ip.0, data.username, data.password, data.captcha).await?;
+
+    Ok(ApiData(LoginResult {
+        token
+    }))
+}
@@ -0,0 +1,59 @@
+use rocket::{Rocket, Build, http::Status};
+
+use crate::{error::HTTPError, request_handler::AuthTokenError};
+use envelope::{ApiError, EnvelopeError};
+
+pub mod envelope;
+pub mod types;
+mod login;
+pub mod user;
+pub mod exam;
+pub mod record;
+pub mod schedule;
+
+// Errors that are not returned by a handler, like a failed token guard or an unknown path, are answered in the envelope too.
+#[catch(default)]
+fn err_default(status: Status, req: &rocket::Request) -> ApiError {
+    let (error, challenge) = match status.code {
+        401 => {
+            let reason = AuthTokenError::from_cache(req);
+            (reason.error(), Some(reason.challenge()))
+        },
+        403 => (HTTPError::Forbidden, None),
+        404 => (HTTPError::FileNotFound, None),
+        502 => (HTTPError::RemoteServiceUnavailable, None),
+        code if code >= 500 => (HTTPError::ServerError, None),
+        _ => (HTTPError::BadRequest, None)
+    };
+
+    ApiError {
+        status,
+        error: EnvelopeError {
+            code: error.code(),
+            message: error.message(),
+            at: None
+        },
+        challenge
+    }
+}
+
+pub fn init_v2_api(server: Rocket<Build>) -> Rocket<Build> {
+    server
+        .register("/v2", catchers![err_default])
+        .mount("/v2", routes![
+            // Login data
+            login::api_session,
+            login::api_captcha,
+            login::api,
+
+            // User data
+            user::api,
+            user::api_profile,
+            exam::api,
+            exam::api_score,
+            record::api_lack,
+            record::api_reward_and_punish,
+            schedule::api,
+            schedule::api_list
+        ])
+}
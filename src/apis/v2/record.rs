@@ -0,0 +1,75 @@
+use crate::{
+    types::{AuthToken, LackCollect, LackTypedRecord, RewardAndPunishCollect, RewardAndPunishDetailValue},
+    request_handler::AuthorizationToken,
+    apis::v1::{get_lack::{fetch_lack, to_typed_records}, get_reward_and_punish::get_reward_and_punish}
+};
+use super::{envelope::{ApiData, ApiResponse}, types::{Lack, LackRecord, LackTotals, RewardAndPunish, RewardAndPunishRecord}};
+
+const LACK_API_PATH: &str = "/v2/lack";
+const REWARD_AND_PUNISH_API_PATH: &str = "/v2/rewardAndPunish";
+
+impl From<LackTypedRecord> for LackRecord {
+    fn from(record: LackTypedRecord) -> Self {
+        LackRecord {
+            date: record.date,
+            iso_date: record.isoDate,
+            weekday: record.weekday,
+            period: record.period,
+            period_label: record.periodLabel,
+            kind: record.kind,
+            code: record.code
+        }
+    }
+}
+
+impl From<RewardAndPunishDetailValue> for RewardAndPunishRecord {
+    fn from(record: RewardAndPunishDetailValue) -> Self {
+        RewardAndPunishRecord {
+            r#type: record.r#type,
+            reason: record.reason,
+            start: record.start,
+            signed: record.signed,
+            execute: record.execute,
+            sold: record.sold,
+            year: record.year,
+            gregorian_year: record.gregorianYear,
+            iso_start: record.isoStart,
+            iso_signed: record.isoSigned,
+            iso_execute: record.isoExecute,
+            iso_sold: record.isoSold
+        }
+    }
+}
+
+pub fn to_lack(data: LackCollect, periods: &[String]) -> Lack {
+    Lack {
+        records: to_typed_records(&data.record, periods).into_iter().map(LackRecord::from).collect(),
+        totals: LackTotals {
+            first_term: data.total.termUp,
+            second_term: data.total.termDown
+        }
+    }
+}
+
+impl From<RewardAndPunishCollect> for RewardAndPunish {
+    fn from(data: RewardAndPunishCollect) -> Self {
+        RewardAndPunish {
+            records: data.detail.into_iter().map(RewardAndPunishRecord::from).collect(),
+            summary: data.status
+        }
+    }
+}
+
+#[get("/lack")]
+pub async fn api_lack(auth: AuthorizationToken<AuthToken>) -> ApiResponse<Lack> {
+    let (data, periods) = fetch_lack(LACK_API_PATH, &auth.0).await?;
+
+    Ok(ApiData(to_lack(data, &periods)))
+}
+
+#[get("/rewardAndPunish")]
+pub async fn api_reward_and_punish(auth: AuthorizationToken<AuthToken>) -> ApiResponse<RewardAndPunish> {
+    let data = get_reward_and_punish(REWARD_AND_PUNISH_API_PATH, &auth.0).await?;
+
+    Ok(ApiData(data.into()))
+}
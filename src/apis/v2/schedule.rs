@@ -0,0 +1,54 @@
+use rocket::http::Status;
+
+use crate::{
+    i18n::Message,
+    types::{AuthToken, ScheduleListValues, ScheduleCollect, ScheduleValue},
+    request_handler::AuthorizationToken,
+    apis::v1::get_schedule::{get_schedule, get_own_schedule, get_schedule_list},
+    error::ErrorCode
+};
+use super::{envelope::{ApiData, ApiError, ApiResponse}, types::{Schedule, Lesson}};
+
+const API_PATH: &str = "/v2/schedule";
+const LIST_API_PATH: &str = "/v2/schedules";
+
+impl From<ScheduleValue> for Lesson {
+    fn from(lesson: ScheduleValue) -> Self {
+        Lesson {
+            weekday: lesson.weekday,
+            period: lesson.period,
+            period_label: lesson.periodLabel,
+            subject: lesson.subject,
+            teacher: lesson.teacher
+        }
+    }
+}
+
+impl From<ScheduleCollect> for Schedule {
+    fn from(data: ScheduleCollect) -> Self {
+        Schedule {
+            class: data.class,
+            teacher: data.teacher,
+            lessons: data.schedule.into_iter().map(Lesson::from).collect()
+        }
+    }
+}
+
+#[get("/schedules")]
+pub async fn api_list(auth: AuthorizationToken<AuthToken>) -> ApiResponse<Vec<ScheduleListValues>> {
+    Ok(ApiData(get_schedule_list(LIST_API_PATH, &auth.0).await?))
+}
+
+// The schedule of the class and teacher, or of the user when both are omitted.
+#[get("/schedule?<class>&<teacher>")]
+pub async fn api(auth: AuthorizationToken<AuthToken>, class: Option<&str>, teacher: Option<&str>) -> ApiResponse<Schedule> {
+    let token = auth.0;
+
+    let data = match (class, teacher) {
+        (Some(class), Some(teacher)) => get_schedule(API_PATH, &token, class, teacher).await?,
+        (None, None) => get_own_schedule(API_PATH, &token).await?,
+        _ => return Err(ApiError::new(Status::BadRequest, ErrorCode::MissingArgument, Message::MissingArguments, Some("Argument: class, teacher")))
+    };
+
+    Ok(ApiData(data.into()))
+}
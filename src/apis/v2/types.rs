@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use async_graphql::{SimpleObject, Enum};
+
+use crate::types::{ScoreValue, Weekday, LackPeriod, AbsenceKind, LackStatusValue, RewardAndPunishStatus};
+
+// Fields of v2 are always camelCase, the types are also the GraphQL schema.
+
+// API: /login
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginSession {
+    pub token: String,
+    pub need_captcha: bool
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Captcha {
+    // Data url of the image
+    pub image: String
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginResult {
+    pub token: String
+}
+
+// API: /user
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    pub name: String,
+    pub class_name: String,
+    pub class_number: String,
+    pub gender: String,
+    pub school_number: String
+}
+
+// API: /user/profile
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub birthday: Option<String>,
+    pub id_number: Option<String>,
+    pub address: Option<String>,
+    pub guardian: Option<String>,
+    pub enrollment_date: Option<String>,
+    pub extra: BTreeMap<String, String>,
+    // Data url of the image
+    pub image: String
+}
+
+// API: /exams
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Enum)]
+#[serde(rename_all = "camelCase")]
+pub enum ExamKind {
+    Exam,
+    Regular
+}
+
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+#[graphql(complex)]
+pub struct Exam {
+    pub id: String,
+    pub name: String,
+    pub year: u16,
+    pub gregorian_year: Option<u16>,
+    pub term: u8,
+    pub times: u8,
+    pub kind: ExamKind
+}
+
+// API: /exams/<id>
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct Subject {
+    pub name: String,
+    pub score: ScoreValue,
+    pub gpa: ScoreValue,
+    pub score_failed: bool,
+    pub gpa_failed: bool
+}
+
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct ExamExtra {
+    pub name: String,
+    pub value: String
+}
+
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct ExamScore {
+    pub subjects: Vec<Subject>,
+    pub extra: Vec<ExamExtra>
+}
+
+// API: /lack
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct LackTotals {
+    pub first_term: Vec<LackStatusValue>,
+    pub second_term: Vec<LackStatusValue>
+}
+
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct LackRecord {
+    pub date: String,
+    pub iso_date: Option<String>,
+    pub weekday: Weekday,
+    pub period: LackPeriod,
+    pub period_label: String,
+    pub kind: AbsenceKind,
+    pub code: String
+}
+
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct Lack {
+    pub records: Vec<LackRecord>,
+    pub totals: LackTotals
+}
+
+// API: /rewardAndPunish
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct RewardAndPunishRecord {
+    pub r#type: String,
+    pub reason: String,
+    pub start: String,
+    pub signed: String,
+    pub execute: String,
+    pub sold: Option<String>,
+    pub year: u16,
+    pub gregorian_year: Option<u16>,
+    pub iso_start: Option<String>,
+    pub iso_signed: Option<String>,
+    pub iso_execute: Option<String>,
+    pub iso_sold: Option<String>
+}
+
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct RewardAndPunish {
+    pub records: Vec<RewardAndPunishRecord>,
+    pub summary: Vec<RewardAndPunishStatus>
+}
+
+// API: /schedule
+// `weekday` is from 1 (Monday).
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct Lesson {
+    pub weekday: u8,
+    pub period: u8,
+    pub period_label: String,
+    pub subject: String,
+    pub teacher: Option<String>
+}
+
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct Schedule {
+    pub class: String,
+    pub teacher: String,
+    pub lessons: Vec<Lesson>
+}
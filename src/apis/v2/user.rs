@@ -0,0 +1,48 @@
+use crate::{
+    types::{AuthToken, UserProfileShortValue, UserDataValues},
+    request_handler::AuthorizationToken,
+    apis::v1::get_user_profile::{get_profile, to_user_profile}
+};
+use super::{envelope::{ApiData, ApiResponse}, types::{User, Profile}};
+
+const PROFILE_API_PATH: &str = "/v2/user/profile";
+
+impl From<UserProfileShortValue> for User {
+    fn from(user: UserProfileShortValue) -> Self {
+        User {
+            name: user.userName,
+            class_name: user.className,
+            class_number: user.classNumber,
+            gender: user.gender,
+            school_number: user.schoolNumber
+        }
+    }
+}
+
+pub fn to_profile(datas: Vec<UserDataValues>, image: String) -> Profile {
+    let profile = to_user_profile(datas);
+
+    Profile {
+        birthday: profile.birthday,
+        id_number: profile.idNumber,
+        address: profile.address,
+        guardian: profile.guardian,
+        enrollment_date: profile.enrollmentDate,
+        extra: profile.extra,
+        image
+    }
+}
+
+#[get("/user")]
+pub async fn api(auth: AuthorizationToken<AuthToken>) -> ApiResponse<User> {
+    Ok(ApiData(auth.0.user_data.into()))
+}
+
+// Sensitive fields are masked like v1, unless the owner asks for `unmask=true`.
+#[get("/user/profile?<unmask>")]
+pub async fn api_profile(auth: AuthorizationToken<AuthToken>, unmask: Option<bool>) -> ApiResponse<Profile> {
+    let unmask = unmask.unwrap_or(false);
+    let (datas, image) = get_profile(PROFILE_API_PATH, auth.0, unmask).await?;
+
+    Ok(ApiData(to_profile(datas, image)))
+}
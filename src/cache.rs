@@ -0,0 +1,66 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::{config::read_config, utils::{get_timestamp_millisec, get_time_after_millisec}};
+
+struct CacheValue<T> {
+    value: T,
+    expired: u128
+}
+
+pub struct Cache<T> {
+    storage: Mutex<HashMap<String, CacheValue<T>>>
+}
+
+impl<T> Cache<T>
+where
+    T: Clone
+{
+    pub fn new() -> Self {
+        Self {
+            storage: Mutex::new(HashMap::new())
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<T> {
+        let storage = self.storage.lock().unwrap();
+
+        match storage.get(key) {
+            Some(data) if data.expired > get_timestamp_millisec() => Some(data.value.clone()),
+            _ => None
+        }
+    }
+
+    /// Store the value with the expire time from config. Nothing is stored when the cache is disabled.
+    pub fn set(&self, key: &str, value: T) {
+        self.set_for(key, value, 60 * read_config().cache_expired as u128)
+    }
+
+    /// Store the value for `minutes`. Nothing is stored when the cache is disabled.
+    pub fn set_for(&self, key: &str, value: T, minutes: u128) {
+        if !read_config().cache_enabled {
+            return
+        }
+
+        let now = get_timestamp_millisec();
+        let mut storage = self.storage.lock().unwrap();
+
+        storage.retain(|_, data| data.expired > now);
+        storage.insert(key.to_owned(), CacheValue {
+            value,
+            expired: get_time_after_millisec(minutes)
+        });
+    }
+
+    pub fn remove(&self, key: &str) {
+        self.storage.lock().unwrap().remove(key);
+    }
+}
+
+impl<T> Default for Cache<T>
+where
+    T: Clone
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,252 @@
+use std::{collections::HashMap, fs::{File, read_to_string}, sync::Mutex};
+use lazy_static::lazy_static;
+use openssl::{hash::MessageDigest, rand::rand_bytes};
+use serde::{Deserialize, Serialize};
+use rocket::error;
+
+use crate::{
+    types::{AuthToken, ScheduleValue, LackTypedRecord, PeriodTimeConfig},
+    date::{LOCAL_TIMEZONE, get_local_days, weekday_from_days, format_compact_date, days_from_iso_date},
+    secure::create_hash,
+    utils::{DEFAULT_FILE_PATH, vecu8_to_hex_string, buffer_to_base64_url, get_timestamp, user_key}
+};
+
+pub const FEED_FILE: &str = "calendar_feeds.json";
+
+const PRODUCT_ID: &str = "-//HLHSInfo//HLHSInfo Backend Server//ZH";
+const TIMEZONE_BLOCK: &str = "BEGIN:VTIMEZONE\r\nTZID:Asia/Taipei\r\nBEGIN:STANDARD\r\nDTSTART:19700101T000000\r\nTZOFFSETFROM:+0800\r\nTZOFFSETTO:+0800\r\nTZNAME:CST\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\n";
+
+// Content lines are folded at 75 octets (RFC 5545 3.1).
+const MAX_LINE_OCTETS: usize = 75;
+
+// A feed keeps the last generated calendars, so the calendars are still served after the session is expired.
+// Feeds are saved by the hash of their token, and the session of the user is only kept in memory, keyed by schoolNumber@host.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FeedRecord {
+    pub school_number: String,
+    #[serde(default)]
+    pub host: String,
+    pub schedule: Option<String>,
+    pub lack: Option<String>
+}
+
+impl FeedRecord {
+    fn user_key(&self) -> String {
+        user_key(&self.school_number, &self.host)
+    }
+}
+
+lazy_static! {
+    static ref FEEDS: Mutex<HashMap<String, FeedRecord>> = Mutex::new(load_feeds());
+    static ref SESSIONS: Mutex<HashMap<String, AuthToken>> = Mutex::new(HashMap::new());
+}
+
+fn hash_token(token: &str) -> String {
+    buffer_to_base64_url(&create_hash(MessageDigest::sha256(), token.as_bytes()))
+}
+
+fn load_feeds() -> HashMap<String, FeedRecord> {
+    match read_to_string(format!("{}/{}", *DEFAULT_FILE_PATH, FEED_FILE)) {
+        Ok(data) => serde_json::from_str(&data).unwrap_or_default(),
+        Err(_) => HashMap::new()
+    }
+}
+
+fn save_feeds(feeds: &HashMap<String, FeedRecord>) {
+    let result = File::create(format!("{}/{}", *DEFAULT_FILE_PATH, FEED_FILE))
+        .map_err(|err| err.to_string())
+        .and_then(|file| serde_json::to_writer(file, feeds).map_err(|err| err.to_string()));
+
+    if let Err(err) = result {
+        error!("Cannot save calendar feeds: {}", err);
+    }
+}
+
+// Create a new feed token for the user, the old one is revoked.
+pub fn create_feed(auth: AuthToken) -> String {
+    let mut buffer = [0; 32];
+    rand_bytes(&mut buffer).unwrap();
+    let token = vecu8_to_hex_string(&buffer);
+
+    let mut feeds = FEEDS.lock().unwrap();
+    let feed = FeedRecord {
+        school_number: auth.user_data.schoolNumber.clone(),
+        host: auth.host.clone(),
+        schedule: None,
+        lack: None
+    };
+    let key = feed.user_key();
+
+    feeds.retain(|_, f| f.user_key() != key);
+    feeds.insert(hash_token(&token), feed);
+    save_feeds(&feeds);
+
+    SESSIONS.lock().unwrap().insert(key, auth);
+
+    token
+}
+
+pub fn revoke_feed(auth: &AuthToken) -> bool {
+    let key = user_key(&auth.user_data.schoolNumber, &auth.host);
+    let mut feeds = FEEDS.lock().unwrap();
+    let len = feeds.len();
+
+    feeds.retain(|_, f| f.user_key() != key);
+    SESSIONS.lock().unwrap().remove(&key);
+
+    let removed = len != feeds.len();
+    if removed {
+        save_feeds(&feeds);
+    }
+
+    removed
+}
+
+pub fn get_feed(token: &str) -> Option<FeedRecord> {
+    FEEDS.lock().unwrap().get(&hash_token(token)).cloned()
+}
+
+// Returns the session of the feed when it is not expired, the session is gone after a restart.
+pub fn get_feed_auth(feed: &FeedRecord) -> Option<AuthToken> {
+    let mut sessions = SESSIONS.lock().unwrap();
+    let key = feed.user_key();
+
+    match sessions.get(&key) {
+        Some(auth) if auth.exp > get_timestamp() => Some(auth.clone()),
+        Some(_) => {
+            sessions.remove(&key);
+            None
+        },
+        None => None
+    }
+}
+
+pub fn update_feed_cache(token: &str, schedule: Option<String>, lack: Option<String>) {
+    let mut feeds = FEEDS.lock().unwrap();
+
+    if let Some(feed) = feeds.get_mut(&hash_token(token)) {
+        if schedule.is_some() {
+            feed.schedule = schedule;
+        }
+        if lack.is_some() {
+            feed.lack = lack;
+        }
+        save_feeds(&feeds);
+    }
+}
+
+fn escape_text(text: &str) -> String {
+    text
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// Long lines are split before `MAX_LINE_OCTETS`, and the next part starts with a space.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+
+    folded
+}
+
+fn wrap_calendar(name: &str, events: Vec<String>) -> String {
+    let calendar = format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:{}\r\nCALSCALE:GREGORIAN\r\nX-WR-CALNAME:{}\r\nX-WR-TIMEZONE:{}\r\n{}{}END:VCALENDAR\r\n",
+        PRODUCT_ID,
+        escape_text(name),
+        LOCAL_TIMEZONE,
+        TIMEZONE_BLOCK,
+        events.concat()
+    );
+
+    calendar
+        .split_terminator("\r\n")
+        .map(|line| format!("{}\r\n", fold_line(line)))
+        .collect()
+}
+
+// "08:10" to "081000".
+fn format_time(time: &str) -> String {
+    format!("{}00", time.replace(':', ""))
+}
+
+// Weekly events start from the current week, and repeat `repeat_weeks` times.
+pub fn create_schedule_calendar(school_number: &str, schedule: &[ScheduleValue], periods: &[PeriodTimeConfig], repeat_weeks: u16) -> String {
+    let today = get_local_days();
+    let monday = today - (weekday_from_days(today) as i64 - 1);
+    let stamp = format!("{}T000000Z", format_compact_date(today));
+
+    let events = schedule
+        .iter()
+        .filter_map(|s| {
+            let time = periods.iter().find(|p| p.period == s.period)?;
+            let date = format_compact_date(monday + s.weekday as i64 - 1);
+            let description = s.teacher.as_deref().map(|t| format!("DESCRIPTION:{}\r\n", escape_text(t))).unwrap_or_default();
+
+            Some(format!(
+                "BEGIN:VEVENT\r\nUID:{}-{}-{}@hlhsinfo\r\nDTSTAMP:{}\r\nDTSTART;TZID={}:{}T{}\r\nDTEND;TZID={}:{}T{}\r\nRRULE:FREQ=WEEKLY;COUNT={}\r\nSUMMARY:{}\r\n{}END:VEVENT\r\n",
+                school_number, s.weekday, s.period,
+                stamp,
+                LOCAL_TIMEZONE, date, format_time(&time.start),
+                LOCAL_TIMEZONE, date, format_time(&time.end),
+                repeat_weeks,
+                escape_text(&s.subject),
+                description
+            ))
+        })
+        .collect();
+
+    wrap_calendar("課表", events)
+}
+
+// One all-day event for each date, which lists every period of the date.
+pub fn create_lack_calendar(school_number: &str, records: &[LackTypedRecord]) -> String {
+    let stamp = format!("{}T000000Z", format_compact_date(get_local_days()));
+    let mut dates: Vec<(i64, Vec<&LackTypedRecord>)> = Vec::new();
+
+    for record in records {
+        let days = match record.isoDate.as_deref().and_then(days_from_iso_date) {
+            Some(d) => d,
+            None => continue
+        };
+
+        match dates.iter_mut().find(|(d, _)| *d == days) {
+            Some((_, list)) => list.push(record),
+            None => dates.push((days, vec![record]))
+        }
+    }
+
+    let events = dates
+        .iter()
+        .map(|(days, list)| {
+            let detail = list
+                .iter()
+                .map(|r| format!("{} {}", r.periodLabel, r.code))
+                .collect::<Vec<_>>();
+
+            format!(
+                "BEGIN:VEVENT\r\nUID:lack-{}-{}@hlhsinfo\r\nDTSTAMP:{}\r\nDTSTART;VALUE=DATE:{}\r\nDTEND;VALUE=DATE:{}\r\nSUMMARY:{}\r\nDESCRIPTION:{}\r\nEND:VEVENT\r\n",
+                school_number, days,
+                stamp,
+                format_compact_date(*days),
+                format_compact_date(days + 1),
+                escape_text(&format!("缺曠 {} 節", list.len())),
+                escape_text(&detail.join("\n"))
+            )
+        })
+        .collect();
+
+    wrap_calendar("缺曠紀錄", events)
+}
\ No newline at end of file
@@ -1,8 +1,8 @@
-use std::{fs::File, path::Path, sync::Mutex};
+use std::{fs::File, path::Path, sync::Mutex, net::IpAddr};
 use lazy_static::lazy_static;
 use serde_yaml::{self};
 
-use crate::{types::Config, utils::DEFAULT_FILE_PATH};
+use crate::{types::{Config, MaskFieldConfig, MaskPolicy, LackRuleConfig, MeritRuleConfig, PeriodTimeConfig}, utils::DEFAULT_FILE_PATH};
 
 const CONFIG_FILE: &str = "config.yaml";
 
@@ -16,14 +16,94 @@ impl Default for Config {
             login_status_expired: 60,
             failed_expried: 3600000,
             failed_times_lock: 5,
+            failed_times_lock_ip: default_failed_times_lock_ip(),
+            trusted_proxies: default_trusted_proxies(),
             cache_enabled: true,
             cache_expired: 48,
             check_cycle: 5,
-            enable_record: true
+            enable_record: true,
+            mask_fields: default_mask_fields(),
+            vapid_subject: default_vapid_subject(),
+            webhook_max_retries: default_webhook_max_retries(),
+            webhook_retry_delay: default_webhook_retry_delay(),
+            lack_rules: default_lack_rules(),
+            merit_rule: default_merit_rule(),
+            period_times: default_period_times(),
+            schedule_repeat_weeks: default_schedule_repeat_weeks(),
+            etag_expired: default_etag_expired()
          }
     }
 }
 
+pub fn default_failed_times_lock_ip() -> u8 {
+    20
+}
+
+pub fn default_trusted_proxies() -> Vec<IpAddr> {
+    Vec::new()
+}
+
+pub fn default_period_times() -> Vec<PeriodTimeConfig> {
+    [
+        ("08:10", "09:00"), ("09:10", "10:00"), ("10:10", "11:00"), ("11:10", "12:00"),
+        ("13:10", "14:00"), ("14:10", "15:00"), ("15:10", "16:00"), ("16:10", "17:00")
+    ]
+        .iter()
+        .enumerate()
+        .map(|(i, (start, end))| PeriodTimeConfig { period: i as u8 + 1, start: String::from(*start), end: String::from(*end) })
+        .collect()
+}
+
+pub fn default_schedule_repeat_weeks() -> u16 {
+    20
+}
+
+pub fn default_etag_expired() -> u16 {
+    5
+}
+
+pub fn default_merit_rule() -> MeritRuleConfig {
+    MeritRuleConfig {
+        merit: vec![String::from("嘉獎"), String::from("小功"), String::from("大功")],
+        demerit: vec![String::from("警告"), String::from("小過"), String::from("大過")],
+        ratio: 3,
+        warning: 18,
+        expulsion: 27,
+        cancellable: vec![String::from("警告"), String::from("小過")]
+    }
+}
+
+pub fn default_lack_rules() -> Vec<LackRuleConfig> {
+    vec![
+        LackRuleConfig { name: String::from("曠課"), warning: 28, limit: 42 },
+        LackRuleConfig { name: String::from("事假"), warning: 40, limit: 60 },
+        LackRuleConfig { name: String::from("病假"), warning: 40, limit: 60 }
+    ]
+}
+
+pub fn default_webhook_max_retries() -> u8 {
+    5
+}
+
+pub fn default_webhook_retry_delay() -> u32 {
+    30
+}
+
+pub fn default_vapid_subject() -> String {
+    String::from("mailto:admin@example.com")
+}
+
+pub fn default_mask_fields() -> Vec<MaskFieldConfig> {
+    vec![
+        MaskFieldConfig { name: String::from("身分證"), policy: MaskPolicy::Partial, keep_start: 1, keep_end: 2 },
+        MaskFieldConfig { name: String::from("身份證"), policy: MaskPolicy::Partial, keep_start: 1, keep_end: 2 },
+        MaskFieldConfig { name: String::from("電話"), policy: MaskPolicy::Partial, keep_start: 0, keep_end: 3 },
+        MaskFieldConfig { name: String::from("手機"), policy: MaskPolicy::Partial, keep_start: 0, keep_end: 3 },
+        MaskFieldConfig { name: String::from("地址"), policy: MaskPolicy::Redact, keep_start: 0, keep_end: 0 },
+        MaskFieldConfig { name: String::from("住址"), policy: MaskPolicy::Redact, keep_start: 0, keep_end: 0 }
+    ]
+}
+
 lazy_static! {
     static ref CONFIG: Mutex<Option<Config>> = Mutex::new(None);
 }
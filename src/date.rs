@@ -0,0 +1,104 @@
+use crate::utils::get_timestamp;
+
+// Dates on the school system are in ROC (Minguo) calendar, which year 1 is 1912.
+pub const ROC_YEAR_OFFSET: u16 = 1911;
+// The school is in Taiwan (UTC+8, no daylight saving time).
+pub const LOCAL_TIMEZONE: &str = "Asia/Taipei";
+pub const LOCAL_OFFSET_SECOND: u64 = 8 * 3600;
+
+pub fn roc_year_to_gregorian(year: u16) -> u16 {
+    year + ROC_YEAR_OFFSET
+}
+
+// An academic year starts in August, e.g. academic year 112 is 2023-08 to 2024-07, so 2023 is returned.
+pub fn academic_year_to_gregorian(year: u16) -> Option<u16> {
+    if year == 0 {
+        return None
+    }
+
+    Some(roc_year_to_gregorian(year))
+}
+
+fn split_numbers(date: &str) -> Option<Vec<u32>> {
+    date
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<u32>().ok())
+        .collect()
+}
+
+// "112/09/01", "112.09.01" or "1120901" to "2023-09-01".
+pub fn parse_roc_date(date: &str) -> Option<String> {
+    let mut parts = split_numbers(date)?;
+
+    if parts.len() == 1 && date.trim().len() == 7 {
+        let value = parts[0];
+        parts = vec![value / 10000, value / 100 % 100, value % 100];
+    }
+
+    match parts[..] {
+        [year, month, day] if year > 0 && (1..=12).contains(&month) && (1..=31).contains(&day) => {
+            Some(format!("{:04}-{:02}-{:02}", roc_year_to_gregorian(year as u16), month, day))
+        },
+        _ => None
+    }
+}
+
+// "112/09" to "2023-09".
+pub fn parse_roc_month(month: &str) -> Option<String> {
+    match split_numbers(month)?[..] {
+        [year, month, ..] if year > 0 && (1..=12).contains(&month) => {
+            Some(format!("{:04}-{:02}", roc_year_to_gregorian(year as u16), month))
+        },
+        _ => None
+    }
+}
+
+// Days since 1970-01-01 to (year, month, day). Source: http://howardhinnant.github.io/date_algorithms.html
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+
+    (yoe + era * 400 + if month <= 2 { 1 } else { 0 }, month, day)
+}
+
+// Days since 1970-01-01 of today in local timezone.
+pub fn get_local_days() -> i64 {
+    ((get_timestamp() + LOCAL_OFFSET_SECOND) / 86400) as i64
+}
+
+// 1 is Monday and 7 is Sunday.
+pub fn weekday_from_days(days: i64) -> u8 {
+    ((days + 3).rem_euclid(7) + 1) as u8
+}
+
+// "2023-09-01" to "20230901", which is used by iCalendar.
+pub fn format_compact_date(days: i64) -> String {
+    let (year, month, day) = civil_from_days(days);
+
+    format!("{:04}{:02}{:02}", year, month, day)
+}
+
+pub fn days_from_iso_date(date: &str) -> Option<i64> {
+    let parts = split_numbers(date)?;
+
+    match parts[..] {
+        [year, month, day] => {
+            let year = year as i64 - if month <= 2 { 1 } else { 0 };
+            let era = year.div_euclid(400);
+            let yoe = year.rem_euclid(400);
+            let mp = (month as i64 + 9) % 12;
+            let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+            let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+            Some(era * 146097 + doe - 719468)
+        },
+        _ => None
+    }
+}
\ No newline at end of file
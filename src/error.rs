@@ -1,3 +1,8 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::i18n::Message;
+
 pub enum HTTPError {
     BadRequest,
     Unauthorized,
@@ -7,23 +12,76 @@ pub enum HTTPError {
     RemoteServiceUnavailable,
 
     AuthorizationTokenMissMatch,
+    AuthorizationTokenInvalid,
+    AuthorizationTokenExpired,
     NotAValidHost,
     SessionExpired
 }
 
 impl HTTPError {
-    pub fn message(&self) -> &'static str {
+    pub fn message(&self) -> Message {
         match *self {
-            HTTPError::BadRequest => "This request is incorrect. Please check your request.",
-            HTTPError::Unauthorized => "You have to be authorized to access this api.",
-            HTTPError::Forbidden => "You have no premission to access this api. Authorization token might be expired.",
-            HTTPError::FileNotFound => "Cannot found api. Please check your api path.",
-            HTTPError::ServerError => "Our server is unreachable this time.",
-            HTTPError::RemoteServiceUnavailable => "Remote service is unavailable",
-
-            HTTPError::AuthorizationTokenMissMatch => "This authorization token is not for this api",
-            HTTPError::NotAValidHost => "This is not a valid host",
-            HTTPError::SessionExpired => "This login session is expired, please login again"
+            HTTPError::BadRequest => Message::BadRequest,
+            HTTPError::Unauthorized => Message::Unauthorized,
+            HTTPError::Forbidden => Message::Forbidden,
+            HTTPError::FileNotFound => Message::NotFound,
+            HTTPError::ServerError => Message::ServerError,
+            HTTPError::RemoteServiceUnavailable => Message::RemoteServiceUnavailable,
+
+            HTTPError::AuthorizationTokenMissMatch => Message::TokenMismatch,
+            HTTPError::AuthorizationTokenInvalid => Message::TokenInvalid,
+            HTTPError::AuthorizationTokenExpired => Message::TokenExpired,
+            HTTPError::NotAValidHost => Message::InvalidHost,
+            HTTPError::SessionExpired => Message::SessionExpired
+        }
+    }
+}
+
+// Stable code of every error response, clients should match this instead of the message.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    BadRequest,
+    MissingArgument,
+    InvalidArgument,
+    UnsupportedMediaType,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    ServerError,
+    UpstreamDown,
+    UpstreamError,
+    ParseFailed,
+    RenderFailed,
+    FeatureDisabled,
+
+    TokenMismatch,
+    TokenInvalid,
+    TokenExpired,
+    InvalidHost,
+    SessionExpired,
+    LoginFailed,
+    CaptchaWrong,
+    CredentialsWrong,
+    AccountLocked,
+    TooManyAttempts
+}
+
+impl HTTPError {
+    pub fn code(&self) -> ErrorCode {
+        match *self {
+            HTTPError::BadRequest => ErrorCode::BadRequest,
+            HTTPError::Unauthorized => ErrorCode::Unauthorized,
+            HTTPError::Forbidden => ErrorCode::Forbidden,
+            HTTPError::FileNotFound => ErrorCode::NotFound,
+            HTTPError::ServerError => ErrorCode::ServerError,
+            HTTPError::RemoteServiceUnavailable => ErrorCode::UpstreamDown,
+
+            HTTPError::AuthorizationTokenMissMatch => ErrorCode::TokenMismatch,
+            HTTPError::AuthorizationTokenInvalid => ErrorCode::TokenInvalid,
+            HTTPError::AuthorizationTokenExpired => ErrorCode::TokenExpired,
+            HTTPError::NotAValidHost => ErrorCode::InvalidHost,
+            HTTPError::SessionExpired => ErrorCode::SessionExpired
         }
     }
 }
@@ -0,0 +1,92 @@
+use std::io::Write;
+use lazy_static::lazy_static;
+use openssl::hash::{Hasher, MessageDigest};
+use rocket::request::{FromRequest, Outcome};
+use serde::Serialize;
+
+use crate::{
+    types::AuthToken,
+    responder::ETagResponse,
+    secure::create_hash,
+    config::read_config,
+    cache::Cache,
+    i18n::{Locale, DEFAULT_LOCALE, request_locale, with_locale},
+    utils::buffer_to_base64_url
+};
+
+lazy_static! {
+    // The last `ETag` and body sent to a session for an api, keyed by `session_key`.
+    static ref SESSION_BODIES: Cache<(String, String)> = Cache::new();
+}
+
+pub fn create_etag(body: &[u8]) -> String {
+    format!("\"{}\"", buffer_to_base64_url(&create_hash(MessageDigest::sha256(), body)))
+}
+
+// Same tag as `create_etag` of the serialized data in `locale`, without keeping the serialized data.
+fn data_etag<T: Serialize>(data: &T, locale: Option<&'static Locale>) -> String {
+    let mut hasher = Hasher::new(MessageDigest::sha256()).expect("Cannot create hasher");
+    with_locale(locale, || serde_json::to_writer(&mut hasher, data)).unwrap();
+    hasher.flush().unwrap();
+
+    format!("\"{}\"", buffer_to_base64_url(&hasher.finish().expect("Cannot complete hash")))
+}
+
+// Weak comparison (RFC 9110 8.8.3.2), only the opaque tag is compared.
+fn opaque_tag(etag: &str) -> &str {
+    etag.trim().trim_start_matches("W/")
+}
+
+// The session is the school system cookie, `api` also carries the query that changes the body.
+fn session_key(token: &AuthToken, api: &str) -> String {
+    let session = create_hash(MessageDigest::sha256(), format!("{}\n{}", token.host, token.cookie).as_bytes());
+
+    format!("{}:{}", buffer_to_base64_url(&session), api)
+}
+
+// `If-None-Match` of the request, empty when not sent, with the locale the body is written in.
+pub struct IfNoneMatch(Vec<String>, Option<&'static Locale>);
+
+impl IfNoneMatch {
+    // `*` matches any current data, it is only checked after the data has been fetched.
+    pub fn matches(&self, etag: &str) -> bool {
+        self.0.iter().any(|tag| tag == "*" || opaque_tag(tag) == opaque_tag(etag))
+    }
+
+    // The data is always fetched before this. Without a body (304) when the client copy matches,
+    // otherwise the body remembered for the session is reused while its `ETag` is unchanged.
+    pub fn respond<T: Serialize>(&self, token: &AuthToken, api: &str, data: &T) -> ETagResponse {
+        let etag = data_etag(data, self.1);
+
+        if self.matches(&etag) {
+            return ETagResponse { etag, body: None }
+        }
+
+        let key = format!("{}:{}", session_key(token, api), self.1.map_or(DEFAULT_LOCALE, |locale| &locale.tag));
+        let body = match SESSION_BODIES.get(&key) {
+            Some((remembered, body)) if remembered == etag => body,
+            _ => {
+                let body = with_locale(self.1, || serde_json::to_string(data)).unwrap();
+                SESSION_BODIES.set_for(&key, (etag.clone(), body.clone()), read_config().etag_expired as u128);
+                body
+            }
+        };
+
+        ETagResponse { etag, body: Some(body) }
+    }
+}
+#[async_trait]
+impl<'r> FromRequest<'r> for IfNoneMatch {
+    type Error = ();
+
+    async fn from_request(request: &'r rocket::Request<'_>) -> Outcome<Self, Self::Error> {
+        let tags = request.headers()
+            .get("If-None-Match")
+            .flat_map(|value| value.split(','))
+            .map(|tag| tag.trim().to_owned())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+
+        Outcome::Success(IfNoneMatch(tags, request_locale(request)))
+    }
+}
@@ -0,0 +1,278 @@
+use rocket::http::ContentType;
+use rust_xlsxwriter::{Workbook, XlsxError};
+use utoipa::ToSchema;
+
+use crate::{
+    types::{ScoreDataCollect, ScoreValue, AllScoreTestCollect, LackCollect, RewardAndPunishCollect}
+};
+
+const UTF8_BOM: &str = "\u{feff}";
+const SHEET_NAME_MAX_LEN: usize = 31;
+
+#[derive(Debug, FromFormField, Clone, Copy, PartialEq, ToSchema)]
+#[schema(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Xlsx
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx"
+        }
+    }
+
+    pub fn content_type(&self) -> ContentType {
+        match self {
+            ExportFormat::Csv => ContentType::CSV,
+            ExportFormat::Xlsx => ContentType::new("application", "vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ExportCell {
+    Text(String),
+    Number(f64)
+}
+
+impl ExportCell {
+    fn to_text(&self) -> String {
+        match self {
+            ExportCell::Text(s) => s.clone(),
+            ExportCell::Number(n) => n.to_string()
+        }
+    }
+}
+
+impl From<&str> for ExportCell {
+    fn from(value: &str) -> Self {
+        ExportCell::Text(value.to_owned())
+    }
+}
+
+impl From<String> for ExportCell {
+    fn from(value: String) -> Self {
+        ExportCell::Text(value)
+    }
+}
+
+impl From<u8> for ExportCell {
+    fn from(value: u8) -> Self {
+        ExportCell::Number(value as f64)
+    }
+}
+
+impl From<u16> for ExportCell {
+    fn from(value: u16) -> Self {
+        ExportCell::Number(value as f64)
+    }
+}
+
+impl From<f32> for ExportCell {
+    fn from(value: f32) -> Self {
+        ExportCell::Number(value as f64)
+    }
+}
+
+// Scores which are not a number keep their text, like "缺考".
+impl From<&ScoreValue> for ExportCell {
+    fn from(value: &ScoreValue) -> Self {
+        match value.number() {
+            Some(number) => ExportCell::Number(number as f64),
+            None => ExportCell::Text(value.raw().to_owned())
+        }
+    }
+}
+
+impl From<Option<String>> for ExportCell {
+    fn from(value: Option<String>) -> Self {
+        ExportCell::Text(value.unwrap_or_default())
+    }
+}
+
+macro_rules! row {
+    ($($cell:expr),* $(,)?) => {
+        vec![$(ExportCell::from($cell)),*]
+    };
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportSheet {
+    pub name: String,
+    pub rows: Vec<Vec<ExportCell>>
+}
+
+impl ExportSheet {
+    fn new(name: &str, header: Vec<ExportCell>) -> Self {
+        Self {
+            name: name.to_owned(),
+            rows: vec![header]
+        }
+    }
+}
+
+pub fn score_to_sheets(score: &ScoreDataCollect) -> Vec<ExportSheet> {
+    let mut data = ExportSheet::new("Score", row!["科目", "成績", "GPA", "不及格"]);
+    for value in &score.data {
+        let unpass = score.unpass
+            .iter()
+            .filter(|u| u.name == value.name)
+            .map(|u| u.r#type.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        data.rows.push(row![value.name.as_str(), &value.scoreValue, &value.gpaValue, unpass]);
+    }
+
+    let mut extra = ExportSheet::new("Summary", row!["項目", "數值"]);
+    for value in &score.extra {
+        extra.rows.push(row![value.r#type.as_str(), value.value.as_str()]);
+    }
+
+    vec![data, extra]
+}
+
+pub fn all_score_to_sheets(score: &AllScoreTestCollect) -> Vec<ExportSheet> {
+    let mut normal = ExportSheet::new("Normal", row!["科目", "項目", "成績"]);
+    for data in &score.dataNormal {
+        for value in &data.values {
+            normal.rows.push(row![data.name.as_str(), value.name.as_str(), value.value]);
+        }
+    }
+
+    let mut test = ExportSheet::new("Test", row!["科目", "考試", "學年", "學期", "次數", "成績"]);
+    for data in &score.dataTest {
+        for value in &data.values {
+            test.rows.push(row![
+                data.name.as_str(),
+                value.name.name.as_str(),
+                value.name.year,
+                value.name.term,
+                value.name.test,
+                value.value
+            ]);
+        }
+    }
+
+    vec![normal, test]
+}
+
+pub fn lack_to_sheets(lack: &LackCollect, periods: &[String]) -> Vec<ExportSheet> {
+    let mut header = row!["日期", "星期", "isoDate"];
+    header.extend(periods.iter().map(|p| ExportCell::from(p.as_str())));
+
+    let mut record = ExportSheet::new("Record", header);
+    for value in &lack.record {
+        let mut row = row![value.date.as_str(), value.week.as_str(), value.isoDate.clone()];
+        row.extend(value.data.iter().map(|d| ExportCell::from(d.clone())));
+
+        record.rows.push(row);
+    }
+
+    let mut total = ExportSheet::new("Total", row!["假別", "上學期", "下學期"]);
+    for (up, down) in lack.total.termUp.iter().zip(lack.total.termDown.iter()) {
+        total.rows.push(row![up.name.as_str(), up.value, down.value]);
+    }
+
+    vec![record, total]
+}
+
+pub fn reward_and_punish_to_sheets(data: &RewardAndPunishCollect) -> Vec<ExportSheet> {
+    let mut detail = ExportSheet::new("Detail", row!["學年", "類別", "事由", "發生日期", "核定日期", "執行日期", "銷過日期"]);
+    for value in &data.detail {
+        detail.rows.push(row![
+            value.year,
+            value.r#type.as_str(),
+            value.reason.as_str(),
+            value.start.as_str(),
+            value.signed.as_str(),
+            value.execute.as_str(),
+            value.sold.clone()
+        ]);
+    }
+
+    let mut status = ExportSheet::new("Status", row!["類別", "次數"]);
+    for value in &data.status {
+        status.rows.push(row![value.r#type.as_str(), value.times]);
+    }
+
+    vec![detail, status]
+}
+
+// Cells starting with these are run as a formula by spreadsheet programs.
+const FORMULA_PREFIXES: [char; 4] = ['=', '+', '-', '@'];
+
+fn escape_csv(value: &str) -> String {
+    let value = if value.starts_with(FORMULA_PREFIXES) {
+        format!("'{}", value)
+    } else {
+        value.to_owned()
+    };
+
+    if value.contains(['"', ',', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+// CSV has no sheets, so every sheet is written as a block separated by an empty line.
+// The BOM is required by Excel to read the file as UTF-8.
+pub fn to_csv(sheets: &[ExportSheet]) -> Vec<u8> {
+    let blocks = sheets
+        .iter()
+        .map(|sheet| sheet.rows
+            .iter()
+            .map(|row| row
+                .iter()
+                .map(|cell| match cell {
+                    // Negative numbers are written as they are, only text may hold a formula.
+                    ExportCell::Number(_) => cell.to_text(),
+                    ExportCell::Text(text) => escape_csv(text)
+                })
+                .collect::<Vec<_>>()
+                .join(","))
+            .collect::<Vec<_>>()
+            .join("\r\n"))
+        .collect::<Vec<_>>();
+
+    format!("{}{}\r\n", UTF8_BOM, blocks.join("\r\n\r\n")).into_bytes()
+}
+
+fn sheet_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !['[', ']', ':', '*', '?', '/', '\\'].contains(c))
+        .take(SHEET_NAME_MAX_LEN)
+        .collect()
+}
+
+// Text cells are always written as strings, so they are never read as a formula.
+pub fn to_xlsx(sheets: &[ExportSheet]) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+
+    for sheet in sheets {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(sheet_name(&sheet.name))?;
+
+        for (r, row) in sheet.rows.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                match cell {
+                    ExportCell::Number(n) if n.is_finite() => worksheet.write_number(r as u32, c as u16, *n)?,
+                    _ => worksheet.write_string(r as u32, c as u16, cell.to_text())?
+                };
+            }
+        }
+    }
+
+    workbook.save_to_buffer()
+}
+
+pub fn export(sheets: &[ExportSheet], format: ExportFormat) -> Result<Vec<u8>, XlsxError> {
+    match format {
+        ExportFormat::Csv => Ok(to_csv(sheets)),
+        ExportFormat::Xlsx => to_xlsx(sheets)
+    }
+}
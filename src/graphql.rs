@@ -0,0 +1,202 @@
+use async_graphql::{Context, Object, ComplexObject, Enum, Schema, EmptyMutation, EmptySubscription, ErrorExtensions, Request, Response, Result};
+use lazy_static::lazy_static;
+use tokio::sync::Semaphore;
+
+use crate::{
+    i18n::Message,
+    types::{AuthToken, ErrorReturn, ScoreValue, LackPeriod, ScheduleListValues},
+    apis::{
+        v1::{
+            get_user_profile::get_profile, get_available_score::get_available_score, get_score::fetch_score, get_lack::fetch_lack,
+            get_reward_and_punish::get_reward_and_punish, get_schedule::{get_schedule, get_own_schedule, get_schedule_list}
+        },
+        v2::{
+            types::{User, Profile, Exam, ExamScore, Lack, RewardAndPunish, Schedule},
+            user::to_profile, exam::to_exam_score, record::to_lack
+        }
+    },
+    error::ErrorCode
+};
+
+pub const API_PATH: &str = "/graphql";
+
+// Queries of the api are at most 5 levels deep, the introspection query of GraphiQL needs 13.
+pub const MAX_QUERY_DEPTH: usize = 16;
+pub const MAX_QUERY_COMPLEXITY: usize = 1000;
+// Counted for each score field, since every one of them fetches a page.
+pub const SCORE_COMPLEXITY: usize = 50;
+// A list of exams only counts its `score` once, so the pages of one query are also fetched a few at a time.
+const MAX_SCORE_FETCHES: usize = 4;
+
+pub type ApiSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+lazy_static! {
+    pub static ref SCHEMA: ApiSchema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_QUERY_DEPTH)
+        .limit_complexity(MAX_QUERY_COMPLEXITY)
+        .finish();
+}
+
+// Limits the score pages fetched at the same time by one query.
+struct ScoreFetchLimit(Semaphore);
+
+pub async fn execute(request: Request, token: AuthToken) -> Response {
+    SCHEMA.execute(request.data(token).data(ScoreFetchLimit(Semaphore::new(MAX_SCORE_FETCHES)))).await
+}
+
+// The code of the error is in `extensions.code`, like `code` of `ErrorResponse`.
+fn graphql_error(code: ErrorCode, message: Message, at: Option<String>) -> async_graphql::Error {
+    let code = serde_json::to_value(code).ok().and_then(|c| c.as_str().map(str::to_owned)).unwrap_or_default();
+
+    async_graphql::Error::new(message.text()).extend_with(|_, e| {
+        e.set("code", code);
+        if let Some(at) = at {
+            e.set("at", at);
+        }
+    })
+}
+
+fn to_error(err: ErrorReturn) -> async_graphql::Error {
+    let body = err.1.into_inner();
+
+    graphql_error(body.code, body.message, body.wrong.and_then(|w| w.at))
+}
+
+// The `AuthToken` of the request is put in the context by `execute`.
+fn token<'a>(ctx: &Context<'a>) -> Result<&'a AuthToken> {
+    ctx.data::<AuthToken>()
+}
+
+async fn fetch_exam_score(ctx: &Context<'_>, id: &str, year: u16, term: u8) -> Result<ExamScore> {
+    let _permit = ctx.data::<ScoreFetchLimit>()?.0.acquire().await?;
+    let (records, extra) = fetch_score(API_PATH, token(ctx)?, &year.to_string(), &term.to_string(), id).await.map_err(to_error)?;
+
+    Ok(to_exam_score(records, extra))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum ScoreKind {
+    Numeric,
+    Absent,
+    Exempt,
+    NotGraded
+}
+
+#[Object(name = "Score")]
+impl ScoreValue {
+    async fn kind(&self) -> ScoreKind {
+        match self {
+            ScoreValue::Numeric { .. } => ScoreKind::Numeric,
+            ScoreValue::Absent { .. } => ScoreKind::Absent,
+            ScoreValue::Exempt { .. } => ScoreKind::Exempt,
+            ScoreValue::NotGraded { .. } => ScoreKind::NotGraded
+        }
+    }
+
+    async fn value(&self) -> Option<f32> {
+        self.number()
+    }
+
+    #[graphql(name = "raw")]
+    async fn raw_text(&self) -> &str {
+        self.raw()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum LackPeriodType {
+    EarlyStudy,
+    FlagRaising,
+    Class,
+    NoonBreak,
+    Cleaning,
+    Other
+}
+
+#[Object]
+impl LackPeriod {
+    #[graphql(name = "type")]
+    async fn period_type(&self) -> LackPeriodType {
+        match self {
+            LackPeriod::EarlyStudy => LackPeriodType::EarlyStudy,
+            LackPeriod::FlagRaising => LackPeriodType::FlagRaising,
+            LackPeriod::Class(_) => LackPeriodType::Class,
+            LackPeriod::NoonBreak => LackPeriodType::NoonBreak,
+            LackPeriod::Cleaning => LackPeriodType::Cleaning,
+            LackPeriod::Other => LackPeriodType::Other
+        }
+    }
+
+    // Only set for a class period.
+    async fn number(&self) -> Option<u8> {
+        match self {
+            LackPeriod::Class(number) => Some(*number),
+            _ => None
+        }
+    }
+}
+
+// The score page of an exam is only fetched when `score` is queried.
+#[ComplexObject]
+impl Exam {
+    #[graphql(complexity = "SCORE_COMPLEXITY + child_complexity")]
+    async fn score(&self, ctx: &Context<'_>) -> Result<ExamScore> {
+        fetch_exam_score(ctx, &self.id, self.year, self.term).await
+    }
+}
+
+// Every field fetches its own upstream page, so a query only costs the pages it touches.
+pub struct QueryRoot;
+
+#[Object(name = "Query")]
+impl QueryRoot {
+    // Kept in the token, it never fetches.
+    async fn user(&self, ctx: &Context<'_>) -> Result<User> {
+        Ok(token(ctx)?.user_data.clone().into())
+    }
+
+    // Sensitive fields are masked like v1, unless the owner asks for `unmask: true`.
+    async fn profile(&self, ctx: &Context<'_>, #[graphql(default)] unmask: bool) -> Result<Profile> {
+        let (datas, image) = get_profile(API_PATH, token(ctx)?.clone(), unmask).await.map_err(to_error)?;
+
+        Ok(to_profile(datas, image))
+    }
+
+    async fn exams(&self, ctx: &Context<'_>) -> Result<Vec<Exam>> {
+        let exams = get_available_score(API_PATH, token(ctx)?).await.map_err(to_error)?;
+
+        Ok(exams.into_iter().map(Exam::from).collect())
+    }
+
+    #[graphql(complexity = "SCORE_COMPLEXITY + child_complexity")]
+    async fn exam_score(&self, ctx: &Context<'_>, id: String, year: u16, term: u8) -> Result<ExamScore> {
+        fetch_exam_score(ctx, &id, year, term).await
+    }
+
+    async fn lack(&self, ctx: &Context<'_>) -> Result<Lack> {
+        let (data, periods) = fetch_lack(API_PATH, token(ctx)?).await.map_err(to_error)?;
+
+        Ok(to_lack(data, &periods))
+    }
+
+    async fn reward_and_punish(&self, ctx: &Context<'_>) -> Result<RewardAndPunish> {
+        Ok(get_reward_and_punish(API_PATH, token(ctx)?).await.map_err(to_error)?.into())
+    }
+
+    async fn schedules(&self, ctx: &Context<'_>) -> Result<Vec<ScheduleListValues>> {
+        get_schedule_list(API_PATH, token(ctx)?).await.map_err(to_error)
+    }
+
+    // The schedule of the class and teacher, or of the user when both are omitted.
+    async fn schedule(&self, ctx: &Context<'_>, class: Option<String>, teacher: Option<String>) -> Result<Schedule> {
+        let token = token(ctx)?;
+
+        let data = match (class, teacher) {
+            (Some(class), Some(teacher)) => get_schedule(API_PATH, token, &class, &teacher).await,
+            (None, None) => get_own_schedule(API_PATH, token).await,
+            _ => return Err(graphql_error(ErrorCode::MissingArgument, Message::MissingArguments, Some("Argument: class, teacher".to_owned())))
+        };
+
+        Ok(data.map_err(to_error)?.into())
+    }
+}
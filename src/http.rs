@@ -1,3 +1,4 @@
+use std::time::Duration;
 use reqwest::{Client, Response, StatusCode, header::HeaderMap, Method, redirect::Policy};
 use scraper::Html;
 use serde::Serialize;
@@ -146,5 +147,32 @@ where
         return Err(HTTPErrorReturn::StatusCodeError(code));
     }
 
+    Ok(respond)
+}
+
+// `body` is sent as is, so a signature computed over it matches what the receiver gets.
+pub async fn http_post_json(url: &str, body: Vec<u8>, headers: Option<HeaderMap>, timeout: Duration) -> Result<Response, HTTPErrorReturn> {
+    let request = Client::builder()
+        .redirect(Policy::none())
+        .timeout(timeout)
+        .build()
+        .map_err(HTTPErrorReturn::RequestError)?;
+    let mut request_builder = request
+        .post(url)
+        .header("Content-Type", "application/json")
+        .body(body);
+
+    if let Some(header) = headers {
+        request_builder = request_builder.headers(header);
+    }
+
+    let respond = request_builder.send().await.map_err(HTTPErrorReturn::RequestError)?;
+
+    let code = respond.status();
+
+    if !code.is_success() {
+        return Err(HTTPErrorReturn::StatusCodeError(code));
+    }
+
     Ok(respond)
 }
\ No newline at end of file
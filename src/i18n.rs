@@ -0,0 +1,286 @@
+use std::{cell::Cell, collections::HashMap, fs::{read_dir, read_to_string}, path::Path};
+use rocket::Request;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::utils::DEFAULT_FILE_PATH;
+
+pub const LOCALE_DIR: &str = "locales";
+pub const DEFAULT_LOCALE: &str = "en";
+
+macro_rules! messages {
+    ($($name:ident => $id:literal, $text:literal;)*) => {
+        // Every message of a response, the catalogs are keyed by `id` so the English text can change.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Message {
+            $($name),*
+        }
+
+        impl Message {
+            pub const ALL: &'static [Message] = &[$(Message::$name),*];
+
+            pub fn id(&self) -> &'static str {
+                match self {
+                    $(Message::$name => $id),*
+                }
+            }
+
+            // English, used when the locale has no translation.
+            pub fn text(&self) -> &'static str {
+                match self {
+                    $(Message::$name => $text),*
+                }
+            }
+        }
+    };
+}
+
+messages! {
+    // HTTPError
+    BadRequest => "bad_request", "This request is incorrect. Please check your request.";
+    Unauthorized => "unauthorized", "You have to be authorized to access this api.";
+    Forbidden => "forbidden", "You have no premission to access this api. Authorization token might be expired.";
+    NotFound => "not_found", "Cannot found api. Please check your api path.";
+    ServerError => "server_error", "Our server is unreachable this time.";
+    RemoteServiceUnavailable => "remote_service_unavailable", "Remote service is unavailable";
+    TokenMismatch => "token_mismatch", "This authorization token is not for this api";
+    TokenInvalid => "token_invalid", "This authorization token is invalid";
+    TokenExpired => "token_expired", "This authorization token is expired, please login again";
+    InvalidHost => "invalid_host", "This is not a valid host";
+    SessionExpired => "session_expired", "This login session is expired, please login again";
+
+    // Errors
+    MissingArguments => "missing_arguments", "Missing one or more arguments";
+    WrongArguments => "wrong_arguments", "Wrong arguments";
+    ArgumentNotSatisfied => "argument_not_satisfied", "Argument is not satisfied";
+    ContentTypeMissing => "content_type_missing", "Content-Type is not provided";
+    UnsupportedImageSize => "unsupported_image_size", "Unsupported image size";
+    LoginFailed => "login_failed", "Login failed";
+    CaptchaWrong => "captcha_wrong", "Captcha is wrong";
+    CredentialsWrong => "credentials_wrong", "Username or password is wrong";
+    AccountDisabled => "account_disabled", "This account is disabled";
+    TooManyAttempts => "too_many_attempts", "Too many failed login attempts, please try again later";
+    ParseFailed => "parse_failed", "Cannot read the page from remote server";
+    ScoreNotFound => "score_not_found", "Cannot find the score data";
+    ScheduleNotFound => "schedule_not_found", "Cannot find the schedule";
+    WebhookNotFound => "webhook_not_found", "Cannot find the webhook";
+    PushSubscriptionNotFound => "push_subscription_not_found", "Cannot find the push subscription";
+    CalendarFeedNotFound => "calendar_feed_not_found", "Cannot find the calendar feed";
+    NotWatchingScore => "not_watching_score", "This user is not watching score";
+    ScoreWatchingDisabled => "score_watching_disabled", "Score watching is disabled on this server";
+    PushUnavailable => "push_unavailable", "Push notification is unavailable on this server";
+
+    // Success
+    Hello => "hello", "Hello from HLHSInfo Server!";
+    LoginSuccessful => "login_successful", "Login successful!";
+    GetUserProfile => "get_user_profile", "Get user profile successful";
+    GetUserProfileShort => "get_user_profile_short", "Get user profile short successful";
+    GetAvailableScore => "get_available_score", "Get available score data successful";
+    GetScore => "get_score", "Get score info successful";
+    GetAllScores => "get_all_scores", "Get all scores successful";
+    GetScoreAnalytics => "get_score_analytics", "Get score analytics successful";
+    GetLack => "get_lack", "Get lack successful";
+    GetLackAnalytics => "get_lack_analytics", "Get lack analytics successful";
+    GetRewardAndPunish => "get_reward_and_punish", "Get reward and punish successful";
+    GetMeritBalance => "get_merit_balance", "Get merit balance successful";
+    GetSchedule => "get_schedule", "Get schedule successful";
+    GetScheduleList => "get_schedule_list", "Get schedule list successful";
+    GetBatch => "get_batch", "Get batch successful";
+    WatchScore => "watch_score", "Watch score successful";
+    UnwatchScore => "unwatch_score", "Unwatch score successful";
+    GetPushPublicKey => "get_push_public_key", "Get push public key successful";
+    AddPushSubscription => "add_push_subscription", "Add push subscription successful";
+    GetPushSubscription => "get_push_subscription", "Get push subscription successful";
+    DeletePushSubscription => "delete_push_subscription", "Delete push subscription successful";
+    AddWebhook => "add_webhook", "Add webhook successful";
+    GetWebhook => "get_webhook", "Get webhook successful";
+    DeleteWebhook => "delete_webhook", "Delete webhook successful";
+    CreateCalendarFeed => "create_calendar_feed", "Create calendar feed successful";
+    RevokeCalendarFeed => "revoke_calendar_feed", "Revoke calendar feed successful";
+}
+
+const ZH_TW_MESSAGES: [(Message, &str); 56] = [
+    // HTTPError
+    (Message::BadRequest, "請求格式錯誤，請檢查您的請求。"),
+    (Message::Unauthorized, "您必須登入才能使用此 API。"),
+    (Message::Forbidden, "您沒有權限使用此 API，登入憑證可能已過期。"),
+    (Message::NotFound, "找不到此 API，請檢查 API 路徑。"),
+    (Message::ServerError, "伺服器暫時無法使用。"),
+    (Message::RemoteServiceUnavailable, "學校系統暫時無法連線"),
+    (Message::TokenMismatch, "此登入憑證不適用於此 API"),
+    (Message::TokenInvalid, "登入憑證無效"),
+    (Message::TokenExpired, "登入憑證已過期，請重新登入"),
+    (Message::InvalidHost, "此主機不是有效的學校系統"),
+    (Message::SessionExpired, "登入階段已過期，請重新登入"),
+
+    // Errors
+    (Message::MissingArguments, "缺少一個或多個參數"),
+    (Message::WrongArguments, "參數錯誤"),
+    (Message::ArgumentNotSatisfied, "參數不符合要求"),
+    (Message::ContentTypeMissing, "未提供 Content-Type"),
+    (Message::UnsupportedImageSize, "不支援的圖片尺寸"),
+    (Message::LoginFailed, "登入失敗"),
+    (Message::CaptchaWrong, "驗證碼錯誤"),
+    (Message::CredentialsWrong, "帳號或密碼錯誤"),
+    (Message::AccountDisabled, "此帳號已停用"),
+    (Message::TooManyAttempts, "登入失敗次數過多，請稍後再試"),
+    (Message::ParseFailed, "無法讀取學校系統的頁面"),
+    (Message::ScoreNotFound, "找不到成績資料"),
+    (Message::ScheduleNotFound, "找不到課表"),
+    (Message::WebhookNotFound, "找不到此 Webhook"),
+    (Message::PushSubscriptionNotFound, "找不到此推播訂閱"),
+    (Message::CalendarFeedNotFound, "找不到此行事曆訂閱"),
+    (Message::NotWatchingScore, "此使用者未訂閱成績通知"),
+    (Message::ScoreWatchingDisabled, "此伺服器未開放成績通知"),
+    (Message::PushUnavailable, "此伺服器無法使用推播通知"),
+
+    // Success
+    (Message::Hello, "HLHSInfo 伺服器運作中！"),
+    (Message::LoginSuccessful, "登入成功！"),
+    (Message::GetUserProfile, "取得個人資料成功"),
+    (Message::GetUserProfileShort, "取得個人簡介成功"),
+    (Message::GetAvailableScore, "取得成績列表成功"),
+    (Message::GetScore, "取得成績成功"),
+    (Message::GetAllScores, "取得歷次成績成功"),
+    (Message::GetScoreAnalytics, "取得成績分析成功"),
+    (Message::GetLack, "取得缺曠紀錄成功"),
+    (Message::GetLackAnalytics, "取得缺曠分析成功"),
+    (Message::GetRewardAndPunish, "取得獎懲紀錄成功"),
+    (Message::GetMeritBalance, "取得功過相抵成功"),
+    (Message::GetSchedule, "取得課表成功"),
+    (Message::GetScheduleList, "取得課表列表成功"),
+    (Message::GetBatch, "取得資料成功"),
+    (Message::WatchScore, "訂閱成績通知成功"),
+    (Message::UnwatchScore, "取消訂閱成績通知成功"),
+    (Message::GetPushPublicKey, "取得推播公鑰成功"),
+    (Message::AddPushSubscription, "新增推播訂閱成功"),
+    (Message::GetPushSubscription, "取得推播訂閱成功"),
+    (Message::DeletePushSubscription, "刪除推播訂閱成功"),
+    (Message::AddWebhook, "新增 Webhook 成功"),
+    (Message::GetWebhook, "取得 Webhook 成功"),
+    (Message::DeleteWebhook, "刪除 Webhook 成功"),
+    (Message::CreateCalendarFeed, "建立行事曆訂閱成功"),
+    (Message::RevokeCalendarFeed, "撤銷行事曆訂閱成功")
+];
+
+pub struct Locale {
+    pub tag: String,
+    messages: HashMap<String, String>
+}
+
+lazy_static! {
+    // Keyed by the lowercase language tag.
+    static ref LOCALES: HashMap<String, Locale> = {
+        let mut locales = HashMap::new();
+
+        locales.insert(DEFAULT_LOCALE.to_owned(), Locale { tag: DEFAULT_LOCALE.to_owned(), messages: HashMap::new() });
+        locales.insert("zh-tw".to_owned(), Locale {
+            tag: "zh-TW".to_owned(),
+            messages: ZH_TW_MESSAGES.iter().map(|(k, v)| (k.id().to_owned(), v.to_string())).collect()
+        });
+
+        // Every `<tag>.json` in the locale folder is a catalog of message id to translation, it extends the built-in catalog of the same tag.
+        if let Ok(entries) = read_dir(Path::new(&*DEFAULT_FILE_PATH).join(LOCALE_DIR)) {
+            for path in entries.flatten().map(|e| e.path()).filter(|p| p.extension().is_some_and(|e| e == "json")) {
+                let tag = match path.file_stem().and_then(|s| s.to_str()) {
+                    Some(tag) => tag.to_owned(),
+                    None => continue
+                };
+                let messages = match read_to_string(&path).ok().and_then(|s| serde_json::from_str::<HashMap<String, String>>(&s).ok()) {
+                    Some(messages) => messages,
+                    None => {
+                        println!("Cannot read locale file {}", path.display());
+                        continue
+                    }
+                };
+
+                for id in messages.keys().filter(|id| !Message::ALL.iter().any(|m| m.id() == id.as_str())) {
+                    println!("Unknown message id \"{}\" in locale file {}", id, path.display());
+                }
+
+                locales
+                    .entry(tag.to_lowercase())
+                    .or_insert_with(|| Locale { tag, messages: HashMap::new() })
+                    .messages
+                    .extend(messages);
+            }
+        }
+
+        locales
+    };
+}
+
+thread_local! {
+    // Locale of the response being serialized on this thread, see `with_locale`.
+    static CURRENT_LOCALE: Cell<Option<&'static Locale>> = const { Cell::new(None) };
+}
+
+impl Locale {
+    pub fn translate(&self, message: Message) -> &str {
+        self.messages.get(message.id()).map_or(message.text(), String::as_str)
+    }
+}
+
+// Pick the best locale in `Accept-Language`, a region falls back to another region of the same language (zh-HK -> zh-TW).
+pub fn negotiate(accept_language: &str) -> Option<&'static Locale> {
+    let mut ranges = accept_language
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let tag = parts.next()?.trim().to_lowercase();
+            let quality = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+
+            (!tag.is_empty() && tag != "*" && quality > 0.0).then_some((tag, quality))
+        })
+        .collect::<Vec<_>>();
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    ranges.iter().find_map(|(tag, _)| {
+        LOCALES.get(tag).or_else(|| {
+            let language = tag.split('-').next().unwrap_or(tag);
+            LOCALES
+                .iter()
+                .filter(|(key, _)| key.split('-').next() == Some(language))
+                .min_by_key(|(key, _)| key.as_str())
+                .map(|(_, locale)| locale)
+        })
+    })
+}
+
+pub fn request_locale(request: &Request<'_>) -> Option<&'static Locale> {
+    request.headers().get_one("Accept-Language").and_then(negotiate)
+}
+
+// Serialize a response with `locale`, every `Message` in it is written in that locale.
+// Serializing never awaits, so the locale cannot leak to another request on the same thread.
+pub fn with_locale<R>(locale: Option<&'static Locale>, serialize: impl FnOnce() -> R) -> R {
+    let previous = CURRENT_LOCALE.with(|current| current.replace(locale));
+    let result = serialize();
+    CURRENT_LOCALE.with(|current| current.set(previous));
+
+    result
+}
+
+impl Serialize for Message {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match CURRENT_LOCALE.with(Cell::get) {
+            Some(locale) => serializer.serialize_str(locale.translate(*self)),
+            None => serializer.serialize_str(self.text())
+        }
+    }
+}
+
+// Read back from the id or the English text.
+impl<'de> Deserialize<'de> for Message {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+
+        Message::ALL
+            .iter()
+            .find(|m| m.id() == value || m.text() == value)
+            .copied()
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown message \"{}\"", value)))
+    }
+}
@@ -0,0 +1,101 @@
+use lazy_static::lazy_static;
+use rocket::http::ContentType;
+use resvg::{usvg::{self, fontdb, TreeParsing, TreeTextToPath}, tiny_skia::{Color, Pixmap, Transform}, Tree};
+use tokio::task::spawn_blocking;
+
+use crate::utils::buffer_to_base64;
+
+pub const BUNDLED_FONT_FAMILY: &str = "Noto Sans TC";
+
+// The CJK subset built into `assets/fonts` by `tools/subset_font.sh`, text is only rendered with these fonts.
+pub const BUNDLED_FONTS: &[&[u8]] = &[
+    include_bytes!("../assets/fonts/NotoSansTC-Regular-Subset.otf"),
+    include_bytes!("../assets/fonts/NotoSansTC-Bold-Subset.otf")
+];
+
+lazy_static! {
+    static ref FONT_DB: fontdb::Database = {
+        let mut db = fontdb::Database::new();
+
+        for font in BUNDLED_FONTS {
+            db.load_font_data(font.to_vec());
+        }
+        db.set_sans_serif_family(BUNDLED_FONT_FAMILY);
+
+        db
+    };
+}
+
+#[derive(Debug)]
+pub enum RenderError {
+    ParseFailed,
+    RenderFailed,
+    EncodeFailed
+}
+
+pub fn detect_image_type(image: &[u8]) -> ContentType {
+    if image.starts_with(&[0x89, b'P', b'N', b'G']) {
+        ContentType::PNG
+    } else if image.starts_with(b"GIF8") {
+        ContentType::GIF
+    } else {
+        ContentType::JPEG
+    }
+}
+
+// Text is converted to paths with `FONT_DB`, otherwise it is not rendered.
+fn render_svg(svg: &str, background: Option<Color>) -> Result<Pixmap, RenderError> {
+    let mut tree = usvg::Tree::from_str(svg, &usvg::Options::default()).map_err(|_| RenderError::ParseFailed)?;
+    tree.convert_text(&FONT_DB);
+    let rtree = Tree::from_usvg(&tree);
+
+    let size = rtree.size.to_int_size();
+    let mut pixmap = Pixmap::new(size.width(), size.height()).ok_or(RenderError::RenderFailed)?;
+
+    if let Some(color) = background {
+        pixmap.fill(color);
+    }
+
+    rtree.render(Transform::default(), &mut pixmap.as_mut());
+
+    Ok(pixmap)
+}
+
+pub fn render_svg_to_png(svg: &str) -> Result<Vec<u8>, RenderError> {
+    render_svg(svg, None)?.encode_png().map_err(|_| RenderError::EncodeFailed)
+}
+
+// Render on a white background and drop the alpha channel, returns (width, height, RGB pixels).
+pub fn render_svg_to_rgb(svg: &str) -> Result<(u32, u32, Vec<u8>), RenderError> {
+    let pixmap = render_svg(svg, Some(Color::WHITE))?;
+    let rgb = pixmap
+        .data()
+        .chunks_exact(4)
+        .flat_map(|p| [p[0], p[1], p[2]])
+        .collect::<Vec<_>>();
+
+    Ok((pixmap.width(), pixmap.height(), rgb))
+}
+
+// Rendering is CPU bound, so run it on the blocking pool instead of the rocket workers.
+pub async fn render_svg_to_png_async(svg: String) -> Result<Vec<u8>, RenderError> {
+    spawn_blocking(move || render_svg_to_png(&svg))
+        .await
+        .map_err(|_| RenderError::RenderFailed)?
+}
+
+// Scale and crop (center) the image into a square PNG, which is used for avatars.
+pub fn resize_image(image: &[u8], size: u32) -> Result<Vec<u8>, RenderError> {
+    let svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{size}" height="{size}" viewBox="0 0 {size} {size}"><image width="{size}" height="{size}" preserveAspectRatio="xMidYMid slice" xlink:href="data:text/plain;base64,{}"/></svg>"#,
+        buffer_to_base64(image)
+    );
+
+    render_svg_to_png(&svg)
+}
+
+pub async fn resize_image_async(image: Vec<u8>, size: u32) -> Result<Vec<u8>, RenderError> {
+    spawn_blocking(move || resize_image(&image, size))
+        .await
+        .map_err(|_| RenderError::RenderFailed)?
+}
\ No newline at end of file
@@ -1,6 +1,7 @@
 #[macro_use] extern crate rocket;
 
 pub mod cors;
+pub mod i18n;
 pub mod config;
 pub mod types;
 pub mod utils;
@@ -10,4 +11,19 @@ pub mod routes;
 pub mod secure;
 pub mod http;
 pub mod request_handler;
-pub mod responder;
\ No newline at end of file
+pub mod responder;
+pub mod cache;
+pub mod image_render;
+pub mod mask;
+pub mod notify;
+pub mod push;
+pub mod webhook;
+pub mod date;
+pub mod calendar;
+pub mod watcher;
+pub mod export;
+pub mod pdf;
+pub mod transcript;
+pub mod timetable;
+pub mod graphql;
+pub mod etag;
\ No newline at end of file
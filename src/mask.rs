@@ -0,0 +1,27 @@
+use crate::{types::{UserDataValues, MaskFieldConfig, MaskPolicy}, utils::mask_string};
+
+const REDACTED: &str = "***";
+
+pub fn mask_value(value: &str, field: &MaskFieldConfig) -> String {
+    if value.is_empty() {
+        return String::new()
+    }
+
+    match field.policy {
+        MaskPolicy::Redact => REDACTED.to_owned(),
+        MaskPolicy::Partial => mask_string(value, field.keep_start, field.keep_end)
+    }
+}
+
+pub fn mask_user_datas(datas: Vec<UserDataValues>, fields: &[MaskFieldConfig]) -> Vec<UserDataValues> {
+    datas
+        .into_iter()
+        .map(|data| match fields.iter().find(|f| data.name.contains(&f.name)) {
+            Some(field) => UserDataValues {
+                value: mask_value(&data.value, field),
+                name: data.name
+            },
+            None => data
+        })
+        .collect()
+}
\ No newline at end of file
@@ -0,0 +1,29 @@
+use serde::Serialize;
+
+use crate::{types::NotifyEvent, push::send_push_to_user, webhook::dispatch, utils::{get_timestamp_millisec, user_key}};
+
+pub const EVENT_NEW_SCORE: &str = "score.new";
+pub const EVENT_SCORE_OPENED: &str = "score.opened";
+pub const EVENT_NEW_LACK: &str = "lack.new";
+pub const EVENT_NEW_REWARD_AND_PUNISH: &str = "rewardAndPunish.new";
+
+pub const EVENTS: [&str; 4] = [EVENT_NEW_SCORE, EVENT_SCORE_OPENED, EVENT_NEW_LACK, EVENT_NEW_REWARD_AND_PUNISH];
+
+pub fn create_event<T>(event: &str, school_number: &str, host: &str, data: &T) -> NotifyEvent
+where
+    T: Serialize
+{
+    NotifyEvent {
+        event: event.to_owned(),
+        schoolNumber: school_number.to_owned(),
+        host: host.to_owned(),
+        timestamp: get_timestamp_millisec(),
+        data: serde_json::to_value(data).unwrap()
+    }
+}
+
+pub async fn notify(webhook: Option<&str>, event: &NotifyEvent) {
+    send_push_to_user(&user_key(&event.schoolNumber, &event.host), &serde_json::to_vec(event).unwrap()).await;
+
+    dispatch(webhook, event);
+}
\ No newline at end of file
@@ -0,0 +1,340 @@
+use std::{collections::BTreeMap, io::Write};
+use flate2::{write::ZlibEncoder, Compression};
+use ttf_parser::{Face, GlyphId, name_id};
+
+// A4 in PDF points (1/72 inch).
+pub const A4_WIDTH: u32 = 595;
+pub const A4_HEIGHT: u32 = 842;
+
+// Glyph widths of PDF fonts are in 1/1000 of the font size.
+const GLYPH_SPACE: f32 = 1000.0;
+const TO_UNICODE_BLOCK: usize = 100;
+
+#[derive(Debug)]
+pub enum PdfError {
+    FontParseFailed,
+    // Font collections (TTC) cannot be embedded as FontFile2 or FontFile3.
+    FontCollection
+}
+
+#[derive(Clone)]
+pub struct PdfImage {
+    pub width: u32,
+    pub height: u32,
+    pub rgb: Vec<u8>
+}
+
+// A single OpenType or TrueType font file, it is embedded as it is.
+pub struct PdfFont<'a> {
+    pub data: &'a [u8]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextAnchor {
+    Start,
+    Middle
+}
+
+// Positions are in points from the top left corner of the page, `y` of a text is its baseline.
+pub enum PdfItem {
+    Text { x: f32, y: f32, size: f32, bold: bool, color: &'static str, anchor: TextAnchor, content: String },
+    Rect { x: f32, y: f32, width: f32, height: f32, color: &'static str },
+    Line { x1: f32, y1: f32, x2: f32, y2: f32, width: f32, color: &'static str },
+    // `image` is the index in `PdfDocument::images`.
+    Image { x: f32, y: f32, width: f32, height: f32, image: usize }
+}
+
+pub struct PdfPage {
+    pub items: Vec<PdfItem>
+}
+
+// Images are written once and shared by the pages which draw them.
+pub struct PdfDocument {
+    pub pages: Vec<PdfPage>,
+    pub images: Vec<PdfImage>
+}
+
+fn compress(data: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+// "#d32f2f" -> "0.827 0.184 0.184"
+fn pdf_color(hex: &str) -> String {
+    let hex = hex.trim_start_matches('#');
+
+    (0..3)
+        .map(|i| hex.get(i * 2..i * 2 + 2).and_then(|c| u8::from_str_radix(c, 16).ok()).unwrap_or(0))
+        .map(|c| format!("{:.3}", c as f32 / 255.0))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Objects are numbered when they are reserved, so they can refer to each other before they are written.
+struct PdfWriter {
+    output: Vec<u8>,
+    offsets: Vec<usize>
+}
+
+impl PdfWriter {
+    fn new() -> Self {
+        Self {
+            output: b"%PDF-1.6\n%\xe2\xe3\xcf\xd3\n".to_vec(),
+            offsets: Vec::new()
+        }
+    }
+
+    fn reserve(&mut self) -> usize {
+        self.offsets.push(0);
+        self.offsets.len()
+    }
+
+    fn write(&mut self, id: usize, dictionary: &str, stream: Option<&[u8]>) {
+        self.offsets[id - 1] = self.output.len();
+        self.output.extend_from_slice(format!("{} 0 obj\n{}\n", id, dictionary).as_bytes());
+
+        if let Some(stream) = stream {
+            self.output.extend_from_slice(b"stream\n");
+            self.output.extend_from_slice(stream);
+            self.output.extend_from_slice(b"\nendstream\n");
+        }
+
+        self.output.extend_from_slice(b"endobj\n");
+    }
+
+    // Compressed with Flate, `dictionary` holds the other entries of the stream.
+    fn write_stream(&mut self, id: usize, dictionary: &str, stream: &[u8]) {
+        let stream = compress(stream);
+        let dictionary = format!("{} /Filter /FlateDecode /Length {}", dictionary, stream.len());
+        self.write(id, &format!("<< {} >>", dictionary.trim_start()), Some(&stream));
+    }
+
+    fn finish(mut self, root: usize) -> Vec<u8> {
+        let xref = self.output.len();
+        let entries = self.offsets
+            .iter()
+            .map(|offset| format!("{:010} 00000 n \n", offset))
+            .collect::<String>();
+
+        self.output.extend_from_slice(format!(
+            "xref\n0 {}\n0000000000 65535 f \n{}trailer\n<< /Size {} /Root {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+            self.offsets.len() + 1, entries, self.offsets.len() + 1, root, xref
+        ).as_bytes());
+
+        self.output
+    }
+}
+
+// Text is written with the glyphs of the embedded font (Identity-H), `ToUnicode` keeps it selectable and searchable.
+struct FontEncoder<'a> {
+    face: Face<'a>,
+    // Glyphs of a CID-keyed CFF font are looked up by their CID, others by the glyph id.
+    cid_keyed: bool,
+    used: BTreeMap<u16, (char, u16)>
+}
+
+impl<'a> FontEncoder<'a> {
+    fn new(font: &PdfFont<'a>) -> Result<Self, PdfError> {
+        if ttf_parser::fonts_in_collection(font.data).is_some() {
+            return Err(PdfError::FontCollection)
+        }
+
+        let face = Face::parse(font.data, 0).map_err(|_| PdfError::FontParseFailed)?;
+        let cid_keyed = face.tables().cff.is_some_and(|cff| cff.glyph_cid(GlyphId(0)).is_some());
+
+        Ok(Self {
+            face,
+            cid_keyed,
+            used: BTreeMap::new()
+        })
+    }
+
+    fn scale(&self, value: f32) -> f32 {
+        value * GLYPH_SPACE / self.face.units_per_em() as f32
+    }
+
+    fn code(&self, glyph: GlyphId) -> u16 {
+        match self.face.tables().cff {
+            Some(cff) if self.cid_keyed => cff.glyph_cid(glyph).unwrap_or(0),
+            _ => glyph.0
+        }
+    }
+
+    // Returns the hex string of the codes and the width of the text in glyph space.
+    fn encode(&mut self, text: &str) -> (String, f32) {
+        let mut hex = String::new();
+        let mut width = 0.0;
+
+        for c in text.chars() {
+            let glyph = self.face.glyph_index(c).unwrap_or(GlyphId(0));
+            let advance = self.scale(self.face.glyph_hor_advance(glyph).unwrap_or(0) as f32).round();
+            let code = self.code(glyph);
+
+            if glyph.0 != 0 {
+                self.used.entry(code).or_insert((c, advance as u16));
+            }
+
+            hex.push_str(&format!("{:04X}", code));
+            width += advance;
+        }
+
+        (hex, width)
+    }
+
+    fn base_font(&self) -> String {
+        let name = self.face
+            .names()
+            .into_iter()
+            .filter(|n| n.name_id == name_id::POST_SCRIPT_NAME)
+            .find_map(|n| n.to_string())
+            .unwrap_or_else(|| "Font".to_owned());
+
+        name.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-').collect()
+    }
+
+    fn to_unicode(&self) -> String {
+        let blocks = self.used
+            .iter()
+            .collect::<Vec<_>>()
+            .chunks(TO_UNICODE_BLOCK)
+            .map(|chunk| {
+                let chars = chunk
+                    .iter()
+                    .map(|(code, (c, _))| {
+                        let unicode = c.encode_utf16(&mut [0; 2]).iter().map(|u| format!("{:04X}", u)).collect::<String>();
+                        format!("<{:04X}> <{}>\n", code, unicode)
+                    })
+                    .collect::<String>();
+
+                format!("{} beginbfchar\n{}endbfchar\n", chunk.len(), chars)
+            })
+            .collect::<String>();
+
+        format!(
+            "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n/CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n{}endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n",
+            blocks
+        )
+    }
+
+    // The whole font file is embedded, the bundled font is already a subset.
+    fn write(&self, writer: &mut PdfWriter, font: &PdfFont<'_>, id: usize) {
+        let (descendant, descriptor, file, to_unicode) = (writer.reserve(), writer.reserve(), writer.reserve(), writer.reserve());
+        let base_font = self.base_font();
+        let bbox = self.face.global_bounding_box();
+        let widths = self.used
+            .iter()
+            .map(|(code, (_, width))| format!("{} [{}]", code, width))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let is_cff = self.face.tables().cff.is_some();
+
+        writer.write(id, &format!(
+            "<< /Type /Font /Subtype /Type0 /BaseFont /{} /Encoding /Identity-H /DescendantFonts [{} 0 R] /ToUnicode {} 0 R >>",
+            base_font, descendant, to_unicode
+        ), None);
+        writer.write(descendant, &format!(
+            "<< /Type /Font /Subtype /{} /BaseFont /{} /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> /FontDescriptor {} 0 R /DW {} /W [{}]{} >>",
+            if is_cff { "CIDFontType0" } else { "CIDFontType2" }, base_font, descriptor, GLYPH_SPACE, widths,
+            if is_cff { "" } else { " /CIDToGIDMap /Identity" }
+        ), None);
+        writer.write(descriptor, &format!(
+            "<< /Type /FontDescriptor /FontName /{} /Flags 4 /FontBBox [{} {} {} {}] /ItalicAngle 0 /Ascent {} /Descent {} /CapHeight {} /StemV 80 /{} {} 0 R >>",
+            base_font,
+            self.scale(bbox.x_min as f32).round(), self.scale(bbox.y_min as f32).round(), self.scale(bbox.x_max as f32).round(), self.scale(bbox.y_max as f32).round(),
+            self.scale(self.face.ascender() as f32).round(), self.scale(self.face.descender() as f32).round(),
+            self.scale(self.face.capital_height().unwrap_or(self.face.ascender()) as f32).round(),
+            if is_cff { "FontFile3" } else { "FontFile2" }, file
+        ), None);
+
+        if is_cff {
+            writer.write_stream(file, "/Subtype /OpenType", font.data);
+        } else {
+            writer.write_stream(file, &format!("/Length1 {}", font.data.len()), font.data);
+        }
+        writer.write_stream(to_unicode, "", self.to_unicode().as_bytes());
+    }
+}
+
+fn page_content(page: &PdfPage, fonts: &mut [FontEncoder]) -> String {
+    let height = A4_HEIGHT as f32;
+    let mut content = String::new();
+
+    for item in &page.items {
+        match item {
+            PdfItem::Text { x, y, size, bold, color, anchor, content: text } => {
+                // The last font is the bold one, or the regular font when there is no bold font.
+                let font = if *bold { fonts.len() - 1 } else { 0 };
+                let (hex, width) = fonts[font].encode(text);
+                let x = match anchor {
+                    TextAnchor::Start => *x,
+                    TextAnchor::Middle => x - width * size / GLYPH_SPACE / 2.0
+                };
+
+                content.push_str(&format!("BT {} rg /F{} {} Tf {:.2} {:.2} Td <{}> Tj ET\n", pdf_color(color), font, size, x, height - y, hex));
+            },
+            PdfItem::Rect { x, y, width, height: h, color } => {
+                content.push_str(&format!("{} rg {:.2} {:.2} {:.2} {:.2} re f\n", pdf_color(color), x, height - y - h, width, h));
+            },
+            PdfItem::Line { x1, y1, x2, y2, width, color } => {
+                content.push_str(&format!("{} RG {} w {:.2} {:.2} m {:.2} {:.2} l S\n", pdf_color(color), width, x1, height - y1, x2, height - y2));
+            },
+            PdfItem::Image { x, y, width, height: h, image } => {
+                content.push_str(&format!("q {:.2} 0 0 {:.2} {:.2} {:.2} cm /Im{} Do Q\n", width, h, x, height - y - h, image));
+            }
+        }
+    }
+
+    content
+}
+
+// Every page is an A4 page, bold text is written with the regular font when `bold` is `None`.
+pub fn create_pdf(document: &PdfDocument, regular: &PdfFont<'_>, bold: Option<&PdfFont<'_>>) -> Result<Vec<u8>, PdfError> {
+    let mut writer = PdfWriter::new();
+    let fonts = [Some(regular), bold].into_iter().flatten().collect::<Vec<_>>();
+    let mut encoders = fonts.iter().map(|font| FontEncoder::new(font)).collect::<Result<Vec<_>, _>>()?;
+
+    let catalog = writer.reserve();
+    let tree = writer.reserve();
+    let font_ids = fonts.iter().map(|_| writer.reserve()).collect::<Vec<_>>();
+    let font_resources = font_ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| format!("/F{} {} 0 R", i, id))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut xobjects = Vec::new();
+    for (i, image) in document.images.iter().enumerate() {
+        let id = writer.reserve();
+        writer.write_stream(id, &format!(
+            "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8",
+            image.width, image.height
+        ), &image.rgb);
+        xobjects.push(format!("/Im{} {} 0 R", i, id));
+    }
+    let xobjects = xobjects.join(" ");
+
+    let mut kids = Vec::new();
+    for page in &document.pages {
+        let (id, content_id) = (writer.reserve(), writer.reserve());
+        let content = page_content(page, &mut encoders);
+
+        writer.write(id, &format!(
+            "<< /Type /Page /Parent {} 0 R /MediaBox [0 0 {} {}] /Resources << /Font << {} >> /XObject << {} >> >> /Contents {} 0 R >>",
+            tree, A4_WIDTH, A4_HEIGHT, font_resources, xobjects, content_id
+        ), None);
+        writer.write_stream(content_id, "", content.as_bytes());
+        kids.push(format!("{} 0 R", id));
+    }
+
+    // Fonts are written last, when the glyphs of every page are known.
+    for ((encoder, font), id) in encoders.iter().zip(fonts).zip(font_ids) {
+        encoder.write(&mut writer, font, id);
+    }
+
+    writer.write(catalog, &format!("<< /Type /Catalog /Pages {} 0 R >>", tree), None);
+    writer.write(tree, &format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), document.pages.len()), None);
+
+    Ok(writer.finish(catalog))
+}
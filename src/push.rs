@@ -0,0 +1,256 @@
+use std::{collections::HashMap, path::Path, fs::{File, read_to_string}, io::prelude::*, sync::Mutex, time::Duration};
+use jsonwebtoken::{encode, Algorithm, Header, EncodingKey};
+use lazy_static::lazy_static;
+use openssl::{
+    bn::BigNumContext,
+    derive::Deriver,
+    ec::{EcGroup, EcKey, EcPoint, PointConversionForm},
+    hash::MessageDigest,
+    nid::Nid,
+    pkey::{PKey, Private},
+    rand::rand_bytes,
+    symm::{encrypt_aead, Cipher}
+};
+use reqwest::{Client, StatusCode, redirect::Policy};
+use rocket::{error, warn};
+
+use crate::{
+    types::{PushSubscriptionValue, PushSubscriptionKeys, VapidClaims},
+    config::read_config,
+    secure::create_hmac,
+    utils::{DEFAULT_FILE_PATH, buffer_to_base64_url, base64_url_to_buffer, vecu8_to_hex_string, get_timestamp_millisec, get_time_after}
+};
+
+pub const VAPID_KEY_FILE: &str = "vapid.pem";
+pub const SUBSCRIPTION_FILE: &str = "push_subscriptions.json";
+
+const PUSH_TTL: &str = "86400";
+const PUSH_TIMEOUT_SECOND: u64 = 10;
+const RECORD_SIZE: u32 = 4096;
+
+lazy_static! {
+    // Push is unavailable when the key cannot be loaded or created.
+    static ref VAPID: Result<VapidKey, String> = VapidKey::new().map_err(|err| {
+        error!("Error loading VAPID key: {}", err);
+        err.to_string()
+    });
+    static ref SUBSCRIPTIONS: Mutex<HashMap<String, Vec<PushSubscriptionValue>>> = Mutex::new(load_subscriptions());
+}
+
+#[derive(Debug)]
+pub enum PushError {
+    KeyUnavailable,
+    InvalidSubscription,
+    EncryptFailed,
+    RequestFailed,
+    Gone
+}
+
+pub struct VapidKey {
+    private: EncodingKey,
+    public: Vec<u8>
+}
+
+impl VapidKey {
+    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        let path = format!("{}/{}", *DEFAULT_FILE_PATH, VAPID_KEY_FILE);
+
+        let key = if !Path::new(&path).exists() {
+            let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+            let key = PKey::from_ec_key(EcKey::generate(&group)?)?;
+
+            File::create(&path)?.write_all(&key.private_key_to_pem_pkcs8()?)?;
+
+            key
+        } else {
+            PKey::private_key_from_pem(read_to_string(&path)?.as_bytes())?
+        };
+
+        let ec_key = key.ec_key()?;
+        let mut ctx = BigNumContext::new()?;
+        let public = ec_key.public_key().to_bytes(ec_key.group(), PointConversionForm::UNCOMPRESSED, &mut ctx)?;
+
+        Ok(Self {
+            private: EncodingKey::from_ec_pem(&key.private_key_to_pem_pkcs8()?)?,
+            public
+        })
+    }
+
+    pub fn sign(&self, endpoint: &str) -> Result<String, PushError> {
+        let url = url::Url::parse(endpoint).map_err(|_| PushError::InvalidSubscription)?;
+        let claims = VapidClaims {
+            aud: url.origin().ascii_serialization(),
+            exp: get_time_after(12 * 60),
+            sub: read_config().vapid_subject
+        };
+
+        encode(&Header::new(Algorithm::ES256), &claims, &self.private).map_err(|_| PushError::EncryptFailed)
+    }
+}
+
+fn vapid() -> Result<&'static VapidKey, PushError> {
+    VAPID.as_ref().map_err(|_| PushError::KeyUnavailable)
+}
+
+pub fn get_vapid_public_key() -> Result<String, PushError> {
+    Ok(buffer_to_base64_url(&vapid()?.public))
+}
+
+fn load_subscriptions() -> HashMap<String, Vec<PushSubscriptionValue>> {
+    let path = format!("{}/{}", *DEFAULT_FILE_PATH, SUBSCRIPTION_FILE);
+
+    match read_to_string(path) {
+        Ok(data) => serde_json::from_str(&data).unwrap_or_default(),
+        Err(_) => HashMap::new()
+    }
+}
+
+fn save_subscriptions(subscriptions: &HashMap<String, Vec<PushSubscriptionValue>>) {
+    let path = format!("{}/{}", *DEFAULT_FILE_PATH, SUBSCRIPTION_FILE);
+
+    let result = File::create(path)
+        .map_err(|err| err.to_string())
+        .and_then(|file| serde_json::to_writer(file, subscriptions).map_err(|err| err.to_string()));
+
+    if let Err(err) = result {
+        error!("Cannot save push subscriptions: {}", err);
+    }
+}
+
+// Subscriptions are kept per user key (schoolNumber@host).
+pub fn add_subscription(user_key: &str, endpoint: &str, keys: PushSubscriptionKeys) -> PushSubscriptionValue {
+    let mut subscriptions = SUBSCRIPTIONS.lock().unwrap();
+    let list = subscriptions.entry(user_key.to_owned()).or_default();
+
+    // The same browser subscribing again replaces its old subscription.
+    list.retain(|s| s.endpoint != endpoint);
+
+    let mut id = [0; 16];
+    rand_bytes(&mut id).unwrap();
+
+    let subscription = PushSubscriptionValue {
+        id: vecu8_to_hex_string(&id),
+        endpoint: endpoint.to_owned(),
+        keys,
+        createdTimestamp: get_timestamp_millisec()
+    };
+
+    list.push(subscription.clone());
+    save_subscriptions(&subscriptions);
+
+    subscription
+}
+
+pub fn list_subscriptions(user_key: &str) -> Vec<PushSubscriptionValue> {
+    SUBSCRIPTIONS.lock().unwrap().get(user_key).cloned().unwrap_or_default()
+}
+
+pub fn remove_subscription(user_key: &str, id: &str) -> bool {
+    let mut subscriptions = SUBSCRIPTIONS.lock().unwrap();
+
+    let removed = match subscriptions.get_mut(user_key) {
+        Some(list) => {
+            let len = list.len();
+            list.retain(|s| s.id != id);
+            len != list.len()
+        },
+        None => false
+    };
+
+    if removed {
+        save_subscriptions(&subscriptions);
+    }
+
+    removed
+}
+
+// Encrypt the payload with "aes128gcm" content encoding (RFC 8291 and RFC 8188).
+pub fn encrypt_payload(keys: &PushSubscriptionKeys, payload: &[u8]) -> Result<Vec<u8>, PushError> {
+    let ua_public = base64_url_to_buffer(&keys.p256dh).ok_or(PushError::InvalidSubscription)?;
+    let auth_secret = base64_url_to_buffer(&keys.auth).ok_or(PushError::InvalidSubscription)?;
+
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).map_err(|_| PushError::EncryptFailed)?;
+    let mut ctx = BigNumContext::new().map_err(|_| PushError::EncryptFailed)?;
+
+    let ua_point = EcPoint::from_bytes(&group, &ua_public, &mut ctx).map_err(|_| PushError::InvalidSubscription)?;
+    let ua_key: PKey<_> = EcKey::from_public_key(&group, &ua_point)
+        .and_then(PKey::from_ec_key)
+        .map_err(|_| PushError::InvalidSubscription)?;
+
+    let as_ec_key = EcKey::generate(&group).map_err(|_| PushError::EncryptFailed)?;
+    let as_public = as_ec_key.public_key()
+        .to_bytes(&group, PointConversionForm::UNCOMPRESSED, &mut ctx)
+        .map_err(|_| PushError::EncryptFailed)?;
+    let as_key: PKey<Private> = PKey::from_ec_key(as_ec_key).map_err(|_| PushError::EncryptFailed)?;
+
+    let ecdh_secret = Deriver::new(&as_key)
+        .and_then(|mut d| {
+            d.set_peer(&ua_key)?;
+            d.derive_to_vec()
+        })
+        .map_err(|_| PushError::EncryptFailed)?;
+
+    let prk_key = create_hmac(MessageDigest::sha256(), &auth_secret, &[&ecdh_secret]);
+    let ikm = create_hmac(MessageDigest::sha256(), &prk_key, &[b"WebPush: info\0", &ua_public, &as_public, &[1]]);
+
+    let mut salt = [0; 16];
+    rand_bytes(&mut salt).map_err(|_| PushError::EncryptFailed)?;
+
+    let prk = create_hmac(MessageDigest::sha256(), &salt, &[&ikm]);
+    let cek = create_hmac(MessageDigest::sha256(), &prk, &[b"Content-Encoding: aes128gcm\0", &[1]]);
+    let nonce = create_hmac(MessageDigest::sha256(), &prk, &[b"Content-Encoding: nonce\0", &[1]]);
+
+    let mut tag = [0; 16];
+    let plain = [payload, &[2]].concat();
+    let cipher = encrypt_aead(Cipher::aes_128_gcm(), &cek[0..16], Some(&nonce[0..12]), &[], &plain, &mut tag)
+        .map_err(|_| PushError::EncryptFailed)?;
+
+    Ok([
+        &salt[..],
+        &RECORD_SIZE.to_be_bytes(),
+        &[as_public.len() as u8],
+        &as_public,
+        &cipher,
+        &tag
+    ].concat())
+}
+
+pub async fn send_push(subscription: &PushSubscriptionValue, payload: &[u8]) -> Result<(), PushError> {
+    let body = encrypt_payload(&subscription.keys, payload)?;
+    let vapid = vapid()?;
+    let token = vapid.sign(&subscription.endpoint)?;
+
+    let respond = Client::builder()
+        .redirect(Policy::none())
+        .timeout(Duration::from_secs(PUSH_TIMEOUT_SECOND))
+        .build()
+        .map_err(|_| PushError::RequestFailed)?
+        .post(&subscription.endpoint)
+        .header("TTL", PUSH_TTL)
+        .header("Content-Encoding", "aes128gcm")
+        .header("Content-Type", "application/octet-stream")
+        .header("Authorization", format!("vapid t={}, k={}", token, buffer_to_base64_url(&vapid.public)))
+        .body(body)
+        .send()
+        .await
+        .map_err(|_| PushError::RequestFailed)?;
+
+    match respond.status() {
+        StatusCode::NOT_FOUND | StatusCode::GONE => Err(PushError::Gone),
+        code if code.is_success() => Ok(()),
+        _ => Err(PushError::RequestFailed)
+    }
+}
+
+// Subscriptions which are gone on the push service are removed.
+pub async fn send_push_to_user(user_key: &str, payload: &[u8]) {
+    for subscription in list_subscriptions(user_key) {
+        match send_push(&subscription, payload).await {
+            Ok(_) => (),
+            Err(PushError::Gone) => {
+                remove_subscription(user_key, &subscription.id);
+            },
+            Err(err) => warn!("Cannot send push to {}: {:?}", subscription.endpoint, err)
+        }
+    }
+}
\ No newline at end of file
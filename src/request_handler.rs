@@ -1,7 +1,10 @@
+use std::{convert::Infallible, net::IpAddr};
 use rocket::{request::{FromRequest, Outcome}, http::Status, serde::json::Json, form::{Form, FromForm}, data::{FromData, self}};
 use serde::de::DeserializeOwned;
 
-use crate::secure::{decode_jwt, JWTError};
+use crate::{secure::{decode_jwt, JWTError}, error::HTTPError, config::read_config};
+
+const AUTH_REALM: &str = "HLHSInfo";
 
 pub enum AuthorizationType {
     LoginAuthToken,
@@ -11,10 +14,46 @@ pub enum AuthorizationType {
 #[derive(Debug, Clone)]
 pub struct AuthorizationToken<T>(pub T);
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum AuthTokenError {
     MissingToken,
-    TokenExpired
+    InvalidToken,
+    TokenExpired,
+    TokenMissMatch
+}
+
+impl AuthTokenError {
+    // Kept in the request local cache, so the 401 catcher can tell why the request was rejected.
+    fn fail<S>(self, request: &rocket::Request<'_>) -> Outcome<S, Self> {
+        request.local_cache(|| Some(self));
+        Outcome::Failure((Status::Unauthorized, self))
+    }
+
+    // Why a request got 401, it is a missing token when no token guard has failed.
+    pub fn from_cache(request: &rocket::Request<'_>) -> Self {
+        request.local_cache(|| None::<Self>).unwrap_or(AuthTokenError::MissingToken)
+    }
+
+    pub fn error(&self) -> HTTPError {
+        match self {
+            AuthTokenError::MissingToken => HTTPError::Unauthorized,
+            AuthTokenError::InvalidToken => HTTPError::AuthorizationTokenInvalid,
+            AuthTokenError::TokenExpired => HTTPError::AuthorizationTokenExpired,
+            AuthTokenError::TokenMissMatch => HTTPError::AuthorizationTokenMissMatch
+        }
+    }
+
+    // `WWW-Authenticate` header of the 401 response (RFC 6750).
+    pub fn challenge(&self) -> String {
+        let description = match self {
+            AuthTokenError::MissingToken => return format!("Bearer realm=\"{}\"", AUTH_REALM),
+            AuthTokenError::InvalidToken => "The authorization token is invalid",
+            AuthTokenError::TokenExpired => "The authorization token is expired",
+            AuthTokenError::TokenMissMatch => "The authorization token is not for this api"
+        };
+
+        format!("Bearer realm=\"{}\", error=\"invalid_token\", error_description=\"{}\"", AUTH_REALM, description)
+    }
 }
 
 #[async_trait]
@@ -31,16 +70,40 @@ where
             if auth_parts.len() == 2 && auth_parts[0] == "Bearer" {
                 let auth = auth_parts[1].to_string();
                 
-                match decode_jwt::<T>(&auth) {
-                    Ok(tkn) => return Outcome::Success(AuthorizationToken(tkn.claims)),
+                return match decode_jwt::<T>(&auth) {
+                    Ok(tkn) => Outcome::Success(AuthorizationToken(tkn.claims)),
                     Err(err) => match err {
-                        JWTError::Expired => return Outcome::Failure((Status::Forbidden, AuthTokenError::TokenExpired)),
-                        JWTError::Invalid => return Outcome::Failure((Status::BadGateway, AuthTokenError::MissingToken))
+                        JWTError::Expired => AuthTokenError::TokenExpired.fail(request),
+                        JWTError::ClaimMismatch => AuthTokenError::TokenMissMatch.fail(request),
+                        JWTError::Invalid => AuthTokenError::InvalidToken.fail(request)
                     }
                 }
             }
         }
-        Outcome::Failure((Status::BadGateway, AuthTokenError::MissingToken))
+        AuthTokenError::MissingToken.fail(request)
+    }
+}
+
+// Address of the client, `None` only when the connection has no remote address.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+// The ip header can be set by anyone, so it is only used when the connection comes from a trusted proxy.
+pub fn resolve_client_ip(remote: Option<IpAddr>, header: Option<IpAddr>, trusted_proxies: &[IpAddr]) -> Option<IpAddr> {
+    match remote {
+        Some(ip) if trusted_proxies.contains(&ip) => header.or(remote),
+        _ => remote
+    }
+}
+
+#[async_trait]
+impl<'r> FromRequest<'r> for ClientIp {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r rocket::Request<'_>) -> Outcome<Self, Self::Error> {
+        let remote = request.remote().map(|r| r.ip());
+
+        Outcome::Success(ClientIp(resolve_client_ip(remote, request.real_ip(), &read_config().trusted_proxies)))
     }
 }
 
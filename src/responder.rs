@@ -1,16 +1,98 @@
 use std::io::Cursor;
-use rocket::{response::Responder, Response, http::ContentType};
+use rocket::{response::{Responder, Builder}, Response, http::{ContentType, Header, Status}};
+use serde::Serialize;
+
+use crate::{types::ErrorResponse, i18n::{self, Locale}};
+
+// JSON response, the `Message` of the body is written in the locale of `Accept-Language`.
+#[derive(Debug)]
+pub struct Json<T>(pub T);
+
+impl<T> Json<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+// The body depends on `Accept-Language`, so caches have to keep a copy for every language.
+fn language_headers(response: &mut Builder<'_>, locale: Option<&Locale>) {
+    response.header(Header::new("Vary", "Accept-Language"));
+
+    if let Some(locale) = locale {
+        response.header(Header::new("Content-Language", locale.tag.clone()));
+    }
+}
+
+impl<'r, T: Serialize> Responder<'r, 'static> for Json<T> {
+    fn respond_to(self, request: &'r rocket::Request<'_>) -> rocket::response::Result<'static> {
+        let locale = i18n::request_locale(request);
+        let body = i18n::with_locale(locale, || serde_json::to_string(&self.0)).map_err(|_| Status::InternalServerError)?;
+        let mut response = Response::build();
+
+        language_headers(&mut response, locale);
+        response
+            .header(ContentType::JSON)
+            .sized_body(body.len(), Cursor::new(body))
+            .ok()
+    }
+}
 
 pub struct FileResponse {
     pub content_type: ContentType,
-    pub file: Vec<u8>
+    pub file: Vec<u8>,
+    pub file_name: Option<String>
 }
 
 impl<'r> Responder<'r, 'r> for FileResponse {
     fn respond_to(self, _request: &'r rocket::Request<'_>) -> rocket::response::Result<'r> {
-        Response::build()
+        let mut response = Response::build();
+
+        if let Some(name) = self.file_name {
+            response.header(Header::new("Content-Disposition", format!("attachment; filename=\"{}\"", name)));
+        }
+
+        response
             .header(self.content_type)
             .sized_body(self.file.len(), Cursor::new(self.file))
             .ok()
     }
-}
\ No newline at end of file
+}
+
+
+// Error response of a 401 request, with the `WWW-Authenticate` challenge (RFC 6750).
+pub struct AuthenticateResponse {
+    pub challenge: String,
+    pub body: Json<ErrorResponse>
+}
+
+impl<'r> Responder<'r, 'static> for AuthenticateResponse {
+    fn respond_to(self, request: &'r rocket::Request<'_>) -> rocket::response::Result<'static> {
+        Response::build_from(self.body.respond_to(request)?)
+            .header(Header::new("WWW-Authenticate", self.challenge))
+            .ok()
+    }
+}
+
+// JSON response with an `ETag`, the body is `None` when the client copy is still fresh (304 Not Modified).
+pub struct ETagResponse {
+    pub etag: String,
+    pub body: Option<String>
+}
+
+impl<'r> Responder<'r, 'static> for ETagResponse {
+    fn respond_to(self, request: &'r rocket::Request<'_>) -> rocket::response::Result<'static> {
+        let mut response = Response::build();
+
+        language_headers(&mut response, i18n::request_locale(request));
+        response.header(Header::new("ETag", self.etag));
+
+        match self.body {
+            Some(body) => response
+                .header(ContentType::JSON)
+                .sized_body(body.len(), Cursor::new(body)),
+            None => response.status(Status::NotModified)
+        };
+
+        response.ok()
+    }
+}
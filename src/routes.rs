@@ -1,11 +1,11 @@
-use rocket::{figment::Figment, serde::json::Json, Rocket, Build};
+use rocket::{figment::Figment, Rocket, Build, fairing::AdHoc};
 
-use crate::{apis, types, utils, cors::CORS, config, error::HTTPError};
+use crate::{apis, types, utils, cors::CORS, i18n::Message, config, error::HTTPError, request_handler::AuthTokenError, responder::{AuthenticateResponse, Json}, watcher, webhook};
 
 #[get("/")]
 fn home() -> Json<types::Alive> {
     Json(types::Alive {
-        message: String::from(utils::HELLO_MESSAGE),
+        message: Message::Hello,
         timestamp: utils::get_timestamp_millisec(),
         provider: config::read_config().provider
     })
@@ -14,25 +14,34 @@ fn home() -> Json<types::Alive> {
 #[catch(400)]
 fn err_bad_request() -> Json<types::ErrorResponse> {
     Json(types::ErrorResponse { 
-        message: String::from(HTTPError::BadRequest.message()),
+        code: HTTPError::BadRequest.code(),
+        message: HTTPError::BadRequest.message(),
         timestamp: utils::get_timestamp_millisec(),
         wrong: None
     })
 }
 
 #[catch(401)]
-fn err_unauthorized() -> Json<types::ErrorResponse> {
-    Json(types::ErrorResponse { 
-        message: String::from(HTTPError::Unauthorized.message()),
-        timestamp: utils::get_timestamp_millisec(),
-        wrong: None
-    })
+fn err_unauthorized(req: &rocket::Request) -> AuthenticateResponse {
+    let reason = AuthTokenError::from_cache(req);
+    let error = reason.error();
+
+    AuthenticateResponse {
+        challenge: reason.challenge(),
+        body: Json(types::ErrorResponse {
+            code: error.code(),
+            message: error.message(),
+            timestamp: utils::get_timestamp_millisec(),
+            wrong: None
+        })
+    }
 }
 
 #[catch(403)]
 fn err_forbidden() -> Json<types::ErrorResponse> {
     Json(types::ErrorResponse { 
-        message: String::from(HTTPError::Forbidden.message()),
+        code: HTTPError::Forbidden.code(),
+        message: HTTPError::Forbidden.message(),
         timestamp: utils::get_timestamp_millisec(),
         wrong: None
     })
@@ -41,7 +50,8 @@ fn err_forbidden() -> Json<types::ErrorResponse> {
 #[catch(404)]
 fn err_not_found(req: &rocket::Request) -> Json<types::ErrorResponse> {
     Json(types::ErrorResponse { 
-        message: String::from(HTTPError::FileNotFound.message()),
+        code: HTTPError::FileNotFound.code(),
+        message: HTTPError::FileNotFound.message(),
         timestamp: utils::get_timestamp_millisec(),
         wrong: Some(types::ResponseErrorAt {
             api: Some(req.uri().to_string()),
@@ -54,7 +64,8 @@ fn err_not_found(req: &rocket::Request) -> Json<types::ErrorResponse> {
 #[catch(500)]
 fn err_server_error() -> Json<types::ErrorResponse> {
     Json(types::ErrorResponse { 
-        message: String::from(HTTPError::ServerError.message()),
+        code: HTTPError::ServerError.code(),
+        message: HTTPError::ServerError.message(),
         timestamp: utils::get_timestamp_millisec(),
         wrong: None
     })
@@ -63,7 +74,8 @@ fn err_server_error() -> Json<types::ErrorResponse> {
 #[catch(502)]
 fn err_bad_gateway() -> Json<types::ErrorResponse> {
     Json(types::ErrorResponse { 
-        message: String::from(HTTPError::RemoteServiceUnavailable.message()),
+        code: HTTPError::RemoteServiceUnavailable.code(),
+        message: HTTPError::RemoteServiceUnavailable.message(),
         timestamp: utils::get_timestamp_millisec(),
         wrong: None
     })
@@ -72,6 +84,8 @@ fn err_bad_gateway() -> Json<types::ErrorResponse> {
 fn server_init(config: Figment) -> Rocket<Build> {
     let finit = rocket::custom(config)
         .attach(CORS)
+        .attach(AdHoc::on_liftoff("Score watcher", |_| Box::pin(async { watcher::start_watcher() })))
+        .attach(AdHoc::on_liftoff("Webhook delivery", |_| Box::pin(async { webhook::start_delivery() })))
         .register("/", catchers![err_bad_request, err_unauthorized, err_forbidden, err_not_found, err_server_error, err_bad_gateway])
         .mount("/", routes![home])
         .mount("/v1", routes![home]);
@@ -1,7 +1,7 @@
 use jsonwebtoken::{decode, encode, Algorithm, Header, Validation, EncodingKey, DecodingKey, errors::ErrorKind};
 use serde::{Serialize, de::DeserializeOwned};
 use std::{path::Path, fs::{File, read_to_string}, io::prelude::*};
-use openssl::{pkey::Private, rsa::Rsa, hash::{Hasher, MessageDigest}};
+use openssl::{pkey::{Private, PKey}, rsa::Rsa, hash::{Hasher, MessageDigest}, sign::Signer};
 use lazy_static::lazy_static;
 
 use crate::{types::CacheKeyData, utils::DEFAULT_FILE_PATH};
@@ -25,7 +25,8 @@ pub struct KeyPair {
 
 pub enum JWTError {
     Expired,
-    Invalid
+    Invalid,
+    ClaimMismatch
 }
 
 impl KeyPair {
@@ -76,6 +77,12 @@ impl KeyPair {
             Ok(jwt) => Ok(jwt),
             Err(err) => match err.kind() {
                 ErrorKind::ExpiredSignature => Err(JWTError::Expired),
+                // Claims that fail to parse may be a malformed token, or a valid token of another type.
+                ErrorKind::Json(_) => match decode::<serde_json::Value>(token, &self.public, &validation) {
+                    Ok(_) => Err(JWTError::ClaimMismatch),
+                    Err(err) if *err.kind() == ErrorKind::ExpiredSignature => Err(JWTError::Expired),
+                    Err(_) => Err(JWTError::Invalid)
+                },
                 _ => Err(JWTError::Invalid)
             }
         }
@@ -110,6 +117,17 @@ pub fn create_hash(algorithm: MessageDigest, data: &[u8]) -> Vec<u8> {
     hasher.finish().expect("Cannot complete hash").to_vec()
 }
 
+pub fn create_hmac(algorithm: MessageDigest, key: &[u8], data: &[&[u8]]) -> Vec<u8> {
+    let key = PKey::hmac(key).expect("Cannot create hmac key");
+    let mut signer = Signer::new(algorithm, &key).expect("Cannot create hmac signer");
+
+    for d in data {
+        signer.update(d).expect("Cannot update hmac");
+    }
+
+    signer.sign_to_vec().expect("Cannot complete hmac")
+}
+
 pub fn create_cache_key(school_number: &str, username: &str, class_name: &str) -> CacheKeyData {
     let data = &[school_number.as_bytes(), username.as_bytes(), class_name.as_bytes()].concat();
     let hash = create_hash(MessageDigest::sha512(), &data).to_ascii_lowercase();
@@ -0,0 +1,154 @@
+use utoipa::ToSchema;
+
+use crate::{
+    types::{ScheduleValue, PeriodTimeConfig},
+    utils::escape_xml
+};
+
+pub const MIN_IMAGE_SIZE: u32 = 320;
+pub const MAX_IMAGE_SIZE: u32 = 4096;
+
+const WEEKDAY_LABELS: [&str; 7] = ["一", "二", "三", "四", "五", "六", "日"];
+const FONT_FAMILY: &str = "sans-serif";
+
+#[derive(Debug, FromFormField, Clone, Copy, PartialEq, ToSchema)]
+#[schema(rename_all = "lowercase")]
+pub enum TimetableTheme {
+    Light,
+    Dark
+}
+
+// Common screen sizes in pixels, lock screen wallpapers are rendered in portrait.
+#[derive(Debug, FromFormField, Clone, Copy, PartialEq, ToSchema)]
+#[schema(rename_all = "lowercase")]
+pub enum TimetableDevice {
+    Phone,
+    PhoneLarge,
+    Tablet,
+    TabletLandscape
+}
+
+impl TimetableDevice {
+    pub fn size(&self) -> (u32, u32) {
+        match self {
+            TimetableDevice::Phone => (1080, 2340),
+            TimetableDevice::PhoneLarge => (1290, 2796),
+            TimetableDevice::Tablet => (2048, 2732),
+            TimetableDevice::TabletLandscape => (2732, 2048)
+        }
+    }
+}
+
+struct ThemeColors {
+    background: &'static str,
+    cell: &'static str,
+    header: &'static str,
+    text: &'static str,
+    sub_text: &'static str,
+    accent: &'static str
+}
+
+impl TimetableTheme {
+    fn colors(&self) -> ThemeColors {
+        match self {
+            TimetableTheme::Light => ThemeColors {
+                background: "#f2f2f7",
+                cell: "#ffffff",
+                header: "#e3e9f5",
+                text: "#1c1c1e",
+                sub_text: "#6e6e73",
+                accent: "#3a6fd8"
+            },
+            TimetableTheme::Dark => ThemeColors {
+                background: "#000000",
+                cell: "#1c1c1e",
+                header: "#242a36",
+                text: "#f2f2f7",
+                sub_text: "#98989d",
+                accent: "#7aa5ff"
+            }
+        }
+    }
+}
+
+// Fit a single line of text into the cell, CJK characters are about as wide as the font size.
+fn fit_font_size(text: &str, width: f32, height: f32) -> f32 {
+    let len = text.chars().map(|c| if c.is_ascii() { 0.55 } else { 1.0 }).sum::<f32>().max(1.0);
+
+    (width * 0.9 / len).min(height * 0.32)
+}
+
+fn text(x: f32, y: f32, size: f32, weight: &str, color: &str, content: &str) -> String {
+    format!(
+        r#"<text x="{:.1}" y="{:.1}" font-family="{}" font-size="{:.1}" font-weight="{}" fill="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+        x, y, FONT_FAMILY, size, weight, color, escape_xml(content)
+    )
+}
+
+// Periods are rows and weekdays are columns. Saturday and Sunday are only shown when they have lessons.
+// On portrait screens the top of the image is left empty for the lock screen clock.
+pub fn create_timetable_svg(schedule: &[ScheduleValue], period_times: &[PeriodTimeConfig], width: u32, height: u32, theme: TimetableTheme) -> String {
+    let colors = theme.colors();
+    let (w, h) = (width as f32, height as f32);
+
+    let days = schedule.iter().map(|s| s.weekday).filter(|d| (1..=7).contains(d)).max().unwrap_or(5).max(5) as usize;
+    let periods = schedule.iter().map(|s| s.period).max().unwrap_or(8).max(1) as usize;
+
+    let margin = w * 0.04;
+    let gap = w * 0.008;
+    let top = if h > w * 1.5 { h * 0.3 } else { h * 0.06 };
+    let bottom = h * 0.05;
+
+    let label_width = (w - margin * 2.0) * 0.12;
+    let cell_width = (w - margin * 2.0 - label_width - gap * days as f32) / days as f32;
+    let header_height = (h - top - bottom) * 0.06;
+    let cell_height = (h - top - bottom - header_height - gap * periods as f32) / periods as f32;
+    let radius = cell_width.min(cell_height) * 0.12;
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}"><rect width="{}" height="{}" fill="{}"/>"#,
+        width, height, width, height, width, height, colors.background
+    );
+
+    for (day, label) in WEEKDAY_LABELS.iter().take(days).enumerate() {
+        let x = margin + label_width + gap + day as f32 * (cell_width + gap);
+
+        svg.push_str(&format!(
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="{:.1}" fill="{}"/>"#,
+            x, top, cell_width, header_height, radius, colors.header
+        ));
+        svg.push_str(&text(x + cell_width / 2.0, top + header_height / 2.0, header_height * 0.45, "bold", colors.accent, label));
+    }
+
+    for period in 0..periods {
+        let y = top + header_height + gap + period as f32 * (cell_height + gap);
+        let time = period_times.iter().find(|t| t.period as usize == period + 1);
+
+        let label_size = (label_width * 0.45).min(cell_height * 0.3);
+        match time {
+            Some(time) => {
+                svg.push_str(&text(margin + label_width / 2.0, y + cell_height * 0.38, label_size, "bold", colors.text, &(period + 1).to_string()));
+                svg.push_str(&text(margin + label_width / 2.0, y + cell_height * 0.68, label_size * 0.45, "normal", colors.sub_text, &time.start));
+            },
+            None => svg.push_str(&text(margin + label_width / 2.0, y + cell_height / 2.0, label_size, "bold", colors.text, &(period + 1).to_string()))
+        }
+
+        for day in 0..days {
+            let x = margin + label_width + gap + day as f32 * (cell_width + gap);
+            let lesson = schedule.iter().find(|s| s.weekday as usize == day + 1 && s.period as usize == period + 1);
+
+            svg.push_str(&format!(
+                r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="{:.1}" fill="{}"/>"#,
+                x, y, cell_width, cell_height, radius, colors.cell
+            ));
+
+            if let Some(lesson) = lesson {
+                let size = fit_font_size(&lesson.subject, cell_width, cell_height);
+                svg.push_str(&text(x + cell_width / 2.0, y + cell_height / 2.0, size, "normal", colors.text, &lesson.subject));
+            }
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
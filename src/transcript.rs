@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+use tokio::task::spawn_blocking;
+
+use crate::{
+    types::{UserProfileShortValue, AllScoreTestCollect, LackStatus},
+    image_render::{render_svg_to_rgb, RenderError, BUNDLED_FONTS},
+    pdf::{create_pdf, PdfDocument, PdfFont, PdfImage, PdfItem, PdfPage, TextAnchor, A4_WIDTH, A4_HEIGHT},
+    utils::buffer_to_base64
+};
+
+const MARGIN: f32 = 50.0;
+const CONTENT_TOP: f32 = 150.0;
+const CONTENT_BOTTOM: f32 = 790.0;
+const LINE_HEIGHT: f32 = 18.0;
+const COLUMNS: [f32; 3] = [MARGIN + 5.0, 330.0, 430.0];
+
+// The photo is the only image (`Im0`), it is rendered at 150 dpi.
+const PHOTO_WIDTH: f32 = 70.0;
+const PHOTO_HEIGHT: f32 = 95.0;
+const PHOTO_SCALE: f32 = 150.0 / 72.0;
+
+const PASS_SCORE: f32 = 60.0;
+const TEXT_COLOR: &str = "#000000";
+const UNPASS_COLOR: &str = "#d32f2f";
+const HEADER_COLOR: &str = "#eeeeee";
+const BORDER_COLOR: &str = "#dddddd";
+const FOOTER_COLOR: &str = "#666666";
+
+// (subject, score) of a table.
+type ScoreRows = Vec<(String, f32)>;
+
+enum Line {
+    Section(String),
+    Heading(String),
+    TableHeader(Vec<String>),
+    Row(Vec<String>, Vec<bool>),
+    Text(String),
+    Space
+}
+
+fn text(x: f32, y: f32, size: f32, bold: bool, color: &'static str, anchor: TextAnchor, content: &str) -> PdfItem {
+    PdfItem::Text { x, y, size, bold, color, anchor, content: content.to_owned() }
+}
+
+fn render_line(line: &Line, y: f32) -> Vec<PdfItem> {
+    let baseline = y + LINE_HEIGHT - 5.0;
+
+    match line {
+        Line::Section(s) => vec![text(MARGIN, baseline, 14.0, true, TEXT_COLOR, TextAnchor::Start, s)],
+        Line::Heading(s) => vec![text(MARGIN, baseline, 12.0, true, TEXT_COLOR, TextAnchor::Start, s)],
+        Line::TableHeader(cells) => {
+            let mut items = vec![PdfItem::Rect { x: MARGIN, y, width: A4_WIDTH as f32 - MARGIN * 2.0, height: LINE_HEIGHT, color: HEADER_COLOR }];
+            for (x, cell) in COLUMNS.iter().zip(cells) {
+                items.push(text(*x, baseline, 10.0, true, TEXT_COLOR, TextAnchor::Start, cell));
+            }
+            items
+        },
+        Line::Row(cells, unpass) => {
+            let mut items = vec![PdfItem::Line { x1: MARGIN, y1: y + LINE_HEIGHT, x2: A4_WIDTH as f32 - MARGIN, y2: y + LINE_HEIGHT, width: 0.5, color: BORDER_COLOR }];
+            for ((x, cell), unpass) in COLUMNS.iter().zip(cells).zip(unpass) {
+                items.push(text(*x, baseline, 10.0, false, if *unpass { UNPASS_COLOR } else { TEXT_COLOR }, TextAnchor::Start, cell));
+            }
+            items
+        },
+        Line::Text(s) => vec![text(MARGIN + 5.0, baseline, 10.0, false, TEXT_COLOR, TextAnchor::Start, s)],
+        Line::Space => Vec::new()
+    }
+}
+
+fn score_table(title: String, rows: ScoreRows) -> Vec<Line> {
+    let mut lines = vec![
+        Line::Heading(title),
+        Line::TableHeader(vec!["科目".to_owned(), "成績".to_owned()])
+    ];
+
+    for (name, value) in rows {
+        lines.push(Line::Row(vec![name, value.to_string()], vec![false, value < PASS_SCORE]));
+    }
+
+    lines.push(Line::Space);
+    lines
+}
+
+// One table for each semester of `dataNormal` and for each exam of `dataTest`, in the order of the school year.
+fn score_groups(scores: &AllScoreTestCollect) -> Vec<Vec<Line>> {
+    let mut semesters: Vec<(String, ScoreRows)> = Vec::new();
+    for subject in &scores.dataNormal {
+        for value in &subject.values {
+            match semesters.iter_mut().find(|(name, _)| *name == value.name) {
+                Some((_, rows)) => rows.push((subject.name.clone(), value.value)),
+                None => semesters.push((value.name.clone(), vec![(subject.name.clone(), value.value)]))
+            }
+        }
+    }
+
+    let mut exams: BTreeMap<(u8, u8, u8), (String, ScoreRows)> = BTreeMap::new();
+    for subject in &scores.dataTest {
+        for value in &subject.values {
+            exams
+                .entry((value.name.year, value.name.term, value.name.test))
+                .or_insert_with(|| (value.name.name.clone(), Vec::new()))
+                .1
+                .push((subject.name.clone(), value.value));
+        }
+    }
+
+    let mut groups = section("學期成績", semesters.into_iter().map(|(name, rows)| score_table(name, rows)).collect());
+    groups.extend(section("段考成績", exams.into_values().map(|(name, rows)| score_table(name, rows)).collect()));
+
+    groups
+}
+
+// The section title is kept on the page of its first table.
+fn section(title: &str, mut tables: Vec<Vec<Line>>) -> Vec<Vec<Line>> {
+    if let Some(first) = tables.first_mut() {
+        first.insert(0, Line::Section(title.to_owned()));
+    }
+
+    tables
+}
+
+fn attendance_lines(lack: &LackStatus) -> Vec<Line> {
+    let mut lines = vec![
+        Line::Section("缺曠統計".to_owned()),
+        Line::TableHeader(vec!["假別".to_owned(), "上學期".to_owned(), "下學期".to_owned()])
+    ];
+
+    let rows = lack.termUp
+        .iter()
+        .zip(lack.termDown.iter())
+        .filter(|(up, down)| up.value > 0 || down.value > 0)
+        .map(|(up, down)| Line::Row(vec![up.name.clone(), up.value.to_string(), down.value.to_string()], vec![false; 3]))
+        .collect::<Vec<_>>();
+
+    if rows.is_empty() {
+        lines.push(Line::Text("無缺曠紀錄".to_owned()));
+    } else {
+        lines.extend(rows);
+    }
+
+    lines
+}
+
+// A group is moved to the next page when it does not fit the rest of the page, unless it is longer than a page.
+fn paginate(groups: Vec<Vec<Line>>) -> Vec<Vec<(f32, Line)>> {
+    let mut pages: Vec<Vec<(f32, Line)>> = vec![Vec::new()];
+    let mut y = CONTENT_TOP;
+
+    for group in groups {
+        let height = group.len() as f32 * LINE_HEIGHT;
+        if y + height > CONTENT_BOTTOM && height <= CONTENT_BOTTOM - CONTENT_TOP && y > CONTENT_TOP {
+            pages.push(Vec::new());
+            y = CONTENT_TOP;
+        }
+
+        for line in group {
+            if y + LINE_HEIGHT > CONTENT_BOTTOM {
+                pages.push(Vec::new());
+                y = CONTENT_TOP;
+            }
+
+            pages.last_mut().unwrap().push((y, line));
+            y += LINE_HEIGHT;
+        }
+    }
+
+    pages
+}
+
+// The photo is scaled and cropped to the frame by `image_render`.
+fn render_photo(photo: &[u8]) -> Option<PdfImage> {
+    let svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{}" height="{}" viewBox="0 0 {w} {h}"><image width="{w}" height="{h}" preserveAspectRatio="xMidYMid slice" xlink:href="data:text/plain;base64,{}"/></svg>"#,
+        (PHOTO_WIDTH * PHOTO_SCALE).round(), (PHOTO_HEIGHT * PHOTO_SCALE).round(), buffer_to_base64(photo), w = PHOTO_WIDTH, h = PHOTO_HEIGHT
+    );
+
+    render_svg_to_rgb(&svg).ok().map(|(width, height, rgb)| PdfImage { width, height, rgb })
+}
+
+fn render_header(user: &UserProfileShortValue, photo: bool, date: &str) -> Vec<PdfItem> {
+    let mut items = vec![text(A4_WIDTH as f32 / 2.0, 60.0, 20.0, true, TEXT_COLOR, TextAnchor::Middle, "學生成績單")];
+
+    let infos = [
+        format!("姓名：{}", user.userName),
+        format!("學號：{}", user.schoolNumber),
+        format!("班級：{}　座號：{}", user.className, user.classNumber),
+        format!("列印日期：{}", date)
+    ];
+    for (i, info) in infos.iter().enumerate() {
+        items.push(text(MARGIN, 90.0 + i as f32 * 14.0, 10.0, false, TEXT_COLOR, TextAnchor::Start, info));
+    }
+
+    if photo {
+        items.push(PdfItem::Image { x: A4_WIDTH as f32 - MARGIN - PHOTO_WIDTH, y: 40.0, width: PHOTO_WIDTH, height: PHOTO_HEIGHT, image: 0 });
+    }
+
+    items.push(PdfItem::Line { x1: MARGIN, y1: 142.0, x2: A4_WIDTH as f32 - MARGIN, y2: 142.0, width: 1.0, color: TEXT_COLOR });
+    items
+}
+
+// Every page repeats the student header, so a single page is still identifiable.
+pub fn create_transcript_pages(user: &UserProfileShortValue, photo: Option<&[u8]>, scores: &AllScoreTestCollect, lack: &LackStatus, date: &str, provider: &str) -> PdfDocument {
+    let mut groups = score_groups(scores);
+    groups.push(attendance_lines(lack));
+
+    let pages = paginate(groups);
+    let images = photo.and_then(render_photo).into_iter().collect::<Vec<_>>();
+    let total = pages.len();
+
+    let pages = pages
+        .iter()
+        .enumerate()
+        .map(|(i, lines)| {
+            let mut items = render_header(user, !images.is_empty(), date);
+            for (y, line) in lines {
+                items.extend(render_line(line, *y));
+            }
+            items.push(text(A4_WIDTH as f32 / 2.0, A4_HEIGHT as f32 - 30.0, 9.0, false, FOOTER_COLOR, TextAnchor::Middle, &format!("{} ‧ 第 {} / {} 頁", provider, i + 1, total)));
+
+            PdfPage { items }
+        })
+        .collect();
+
+    PdfDocument { pages, images }
+}
+
+// The text is written with the bundled fonts (regular and bold), so it does not depend on the fonts of the server.
+pub fn render_transcript(document: &PdfDocument) -> Result<Vec<u8>, RenderError> {
+    let regular = PdfFont { data: BUNDLED_FONTS[0] };
+    let bold = PdfFont { data: BUNDLED_FONTS[1] };
+
+    create_pdf(document, &regular, Some(&bold)).map_err(|_| RenderError::RenderFailed)
+}
+
+// Rendering is CPU bound, so run it on the blocking pool instead of the rocket workers.
+pub async fn render_transcript_async(document: PdfDocument) -> Result<Vec<u8>, RenderError> {
+    spawn_blocking(move || render_transcript(&document))
+        .await
+        .map_err(|_| RenderError::RenderFailed)?
+}
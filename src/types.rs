@@ -1,5 +1,10 @@
-use rocket::{response::status::Custom, serde::json::Json};
+use std::{collections::BTreeMap, net::IpAddr};
+use rocket::response::status::Custom;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use async_graphql::{SimpleObject, Enum};
+
+use crate::{responder::Json, error::ErrorCode, i18n::Message};
 
 pub type ErrorReturn = Custom<Json<ErrorResponse>>;
 pub type HTTPResponse<T> = Result<T, ErrorReturn>;
@@ -18,12 +23,90 @@ pub struct Config {
 
     pub failed_expried: u32,
     pub failed_times_lock: u8,
+    #[serde(default = "crate::config::default_failed_times_lock_ip")]
+    pub failed_times_lock_ip: u8,
+
+    // The client IP is read from Rocket's `ip_header` (X-Real-IP) only when the request comes from one of these addresses.
+    #[serde(default = "crate::config::default_trusted_proxies")]
+    pub trusted_proxies: Vec<IpAddr>,
 
     pub cache_enabled: bool,
     pub cache_expired: u16,
     
     pub check_cycle: u16,
-    pub enable_record: bool
+    pub enable_record: bool,
+
+    #[serde(default = "crate::config::default_mask_fields")]
+    pub mask_fields: Vec<MaskFieldConfig>,
+
+    #[serde(default = "crate::config::default_vapid_subject")]
+    pub vapid_subject: String,
+
+    #[serde(default = "crate::config::default_webhook_max_retries")]
+    pub webhook_max_retries: u8,
+    #[serde(default = "crate::config::default_webhook_retry_delay")]
+    pub webhook_retry_delay: u32,
+
+    #[serde(default = "crate::config::default_lack_rules")]
+    pub lack_rules: Vec<LackRuleConfig>,
+
+    #[serde(default = "crate::config::default_merit_rule")]
+    pub merit_rule: MeritRuleConfig,
+
+    #[serde(default = "crate::config::default_period_times")]
+    pub period_times: Vec<PeriodTimeConfig>,
+    #[serde(default = "crate::config::default_schedule_repeat_weeks")]
+    pub schedule_repeat_weeks: u16,
+
+    #[serde(default = "crate::config::default_etag_expired")]
+    pub etag_expired: u16
+}
+
+// Time of a class period in "HH:MM", used by the calendar export.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PeriodTimeConfig {
+    pub period: u8,
+    pub start: String,
+    pub end: String
+}
+
+// `merit` and `demerit` are ordered from the smallest, every `ratio` of one type equals one of the next type.
+// `warning` and `expulsion` are counted in the smallest demerit type.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MeritRuleConfig {
+    pub merit: Vec<String>,
+    pub demerit: Vec<String>,
+    pub ratio: u16,
+    pub warning: u16,
+    pub expulsion: u16,
+    pub cancellable: Vec<String>
+}
+
+// Lack summary counters whose name contains `name` are warned at `warning` and exceeded at `limit`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LackRuleConfig {
+    pub name: String,
+    pub warning: u16,
+    pub limit: u16
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MaskPolicy {
+    Redact,
+    Partial
+}
+
+// Profile fields whose label contains `name` are masked by `policy`.
+// `keep_start` and `keep_end` are only used by the partial policy.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MaskFieldConfig {
+    pub name: String,
+    pub policy: MaskPolicy,
+    #[serde(default)]
+    pub keep_start: usize,
+    #[serde(default)]
+    pub keep_end: usize
 }
 
 pub struct CacheKeyData {
@@ -58,27 +141,29 @@ pub struct AuthToken {
 // Alive JSON
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Alive {
-    pub message: String,
+    pub message: Message,
     pub timestamp: u128,
     pub provider: String
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ResponseErrorAt {
     pub api: Option<String>,
     pub trace: Option<String>,
     pub at: Option<String>
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ErrorResponse {
-    pub message: String,
+    pub code: ErrorCode,
+    #[schema(value_type = String)]
+    pub message: Message,
     pub timestamp: u128,
     pub wrong: Option<ResponseErrorAt>
 }
 
 // API: /getLoginInfo
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[allow(non_snake_case)]
 pub struct LoginInfo {
     pub authToken: String,
@@ -86,34 +171,69 @@ pub struct LoginInfo {
 }
 
 // API: /login
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct Login {
-    pub message: String,
+    #[schema(value_type = String)]
+    pub message: Message,
     pub authtoken: String
 }
 
 // API: /getUserInfo
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UserDataValues {
     pub name: String,
     pub value: String
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[allow(non_snake_case)]
 pub struct UserCollect {
     pub data: Vec<UserDataValues>,
     pub profileImg: String
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UserData {
-    pub message: String,
+    #[schema(value_type = String)]
+    pub message: Message,
     pub data: UserCollect
 }
 
+// API: /getUserInfo?typed=true
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
+#[allow(non_snake_case)]
+pub struct UserProfile {
+    pub birthday: Option<String>,
+    pub idNumber: Option<String>,
+    pub address: Option<String>,
+    pub guardian: Option<String>,
+    pub enrollmentDate: Option<String>,
+    pub extra: BTreeMap<String, String>
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[allow(non_snake_case)]
+pub struct UserTypedCollect {
+    pub data: UserProfile,
+    pub profileImg: String
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserTypedData {
+    #[schema(value_type = String)]
+    pub message: Message,
+    pub data: UserTypedCollect
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum UserDataResponse {
+    Raw(UserData),
+    Typed(UserTypedData)
+}
+
 // API: /getUserInfoShort
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 #[allow(non_snake_case)]
 pub struct UserProfileShortValue {
     pub className: String,
@@ -123,14 +243,15 @@ pub struct UserProfileShortValue {
     pub userName: String
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UserProfileShortData {
-    pub message: String,
+    #[schema(value_type = String)]
+    pub message: Message,
     pub data: UserProfileShortValue
 }
 
 // API: /getAvailableScore
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[allow(non_snake_case)]
 pub struct AvailableScoreValue {
     pub name: String,
@@ -138,50 +259,249 @@ pub struct AvailableScoreValue {
     pub testID: String,
     pub times: u8,
     pub r#type: u8,
-    pub year: u8
+    pub year: u8,
+    pub gregorianYear: Option<u16>
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct AvailableScoreData {
-    pub message: String,
+    #[schema(value_type = String)]
+    pub message: Message,
     pub data: Vec<AvailableScoreValue>
 }
 
 // API: /getScoreInfo
-#[derive(Debug, Serialize, Deserialize)]
+// `raw` is the text on the page, like "87.5", "缺考" or "免修".
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ScoreValue {
+    Numeric { value: f32, raw: String },
+    Absent { raw: String },
+    Exempt { raw: String },
+    NotGraded { raw: String }
+}
+
+// `score` and `gpa` are 0 when the value is not a number, see `scoreValue` and `gpaValue`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[allow(non_snake_case)]
 pub struct ScoreDataValue {
     pub name: String,
     pub score: u8,
-    pub gpa: f32
+    pub gpa: f32,
+    pub scoreValue: ScoreValue,
+    pub gpaValue: ScoreValue
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ScoreExtraData {
     pub r#type: String,
     pub value: String
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ScoreUnpass {
     pub name: String,
     pub r#type: String
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ScoreDataCollect {
     pub data: Vec<ScoreDataValue>,
     pub extra: Vec<ScoreExtraData>,
     pub unpass: Vec<ScoreUnpass>
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ScoreData {
-    pub message: String,
+    #[schema(value_type = String)]
+    pub message: Message,
     pub data: ScoreDataCollect
 }
 
-// API: /getRewAndPun
+// API: /batch
+// Sections which are not requested or have failed are null, the reason of a failure is in `errors` by the section name.
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
+#[allow(non_snake_case)]
+pub struct BatchCollect {
+    pub user: Option<UserProfileShortValue>,
+    pub availableScore: Option<Vec<AvailableScoreValue>>,
+    pub lack: Option<LackCollect>,
+    pub rewardAndPunish: Option<RewardAndPunishCollect>,
+    pub errors: BTreeMap<String, ErrorResponse>
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BatchData {
+    #[schema(value_type = String)]
+    pub message: Message,
+    pub data: BatchCollect
+}
+
+// API: /getScoreAnalytics
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[allow(non_snake_case)]
+pub struct ScoreAnalyticsTest {
+    pub name: String,
+    pub testID: String,
+    pub year: u8,
+    pub gregorianYear: Option<u16>,
+    pub term: u8,
+    pub times: u8,
+    pub r#type: u8,
+    pub average: f32,
+    // Credit weighted, as "加權平均" of the school system.
+    pub weightedAverage: Option<f32>,
+    pub pass: u16,
+    pub fail: u16
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[allow(non_snake_case)]
+pub struct ScoreTrendPoint {
+    pub year: u8,
+    pub gregorianYear: Option<u16>,
+    pub term: u8,
+    pub times: u8,
+    pub score: f32
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScoreSubjectTrend {
+    pub name: String,
+    pub average: f32,
+    pub change: Option<f32>,
+    pub points: Vec<ScoreTrendPoint>
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[allow(non_snake_case)]
+pub struct ScoreAnalyticsCollect {
+    pub average: f32,
+    // Mean of `weightedAverage` of the exams, the credits are only known to the school system.
+    pub meanWeightedAverage: Option<f32>,
+    pub pass: u16,
+    pub fail: u16,
+    pub tests: Vec<ScoreAnalyticsTest>,
+    pub subjects: Vec<ScoreSubjectTrend>
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScoreAnalyticsData {
+    #[schema(value_type = String)]
+    pub message: Message,
+    pub data: ScoreAnalyticsCollect
+}
+
+// API: /watchScore
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[allow(non_snake_case)]
+pub struct WatchScoreCollect {
+    pub webhook: Option<String>,
+    pub expiredTimestamp: u64
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WatchScoreData {
+    #[schema(value_type = String)]
+    pub message: Message,
+    pub data: WatchScoreCollect
+}
+
+// API: /getPushPublicKey
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[allow(non_snake_case)]
+pub struct PushPublicKeyCollect {
+    pub publicKey: String
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PushPublicKeyData {
+    #[schema(value_type = String)]
+    pub message: Message,
+    pub data: PushPublicKeyCollect
+}
+
+// API: /pushSubscription
+#[derive(Debug, Serialize, Deserialize, Clone, FromForm, ToSchema)]
+pub struct PushSubscriptionKeys {
+    pub p256dh: String,
+    pub auth: String
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[allow(non_snake_case)]
+pub struct PushSubscriptionValue {
+    pub id: String,
+    pub endpoint: String,
+    pub keys: PushSubscriptionKeys,
+    pub createdTimestamp: u128
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PushSubscriptionData {
+    #[schema(value_type = String)]
+    pub message: Message,
+    pub data: PushSubscriptionValue
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PushSubscriptionListData {
+    #[schema(value_type = String)]
+    pub message: Message,
+    pub data: Vec<PushSubscriptionValue>
+}
+
 #[derive(Debug, Serialize, Deserialize)]
+pub struct VapidClaims {
+    pub aud: String,
+    pub exp: u64,
+    pub sub: String
+}
+
+// API: /webhook
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[allow(non_snake_case)]
+pub struct WebhookValue {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<String>,
+    pub createdTimestamp: u128
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookCreateCollect {
+    pub webhook: WebhookValue,
+    pub secret: String
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookCreateData {
+    #[schema(value_type = String)]
+    pub message: Message,
+    pub data: WebhookCreateCollect
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookListData {
+    #[schema(value_type = String)]
+    pub message: Message,
+    pub data: Vec<WebhookValue>
+}
+
+// Notification
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[allow(non_snake_case)]
+pub struct NotifyEvent {
+    pub event: String,
+    pub schoolNumber: String,
+    pub host: String,
+    pub timestamp: u128,
+    pub data: serde_json::Value
+}
+
+// API: /getRewAndPun
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[allow(non_snake_case)]
 pub struct RewardAndPunishDetailValue {
     pub execute: String,
     pub reason: String,
@@ -189,74 +509,233 @@ pub struct RewardAndPunishDetailValue {
     pub sold: Option<String>,
     pub start: String,
     pub r#type: String,
-    pub year: u16
+    pub year: u16,
+
+    pub isoExecute: Option<String>,
+    pub isoSigned: Option<String>,
+    pub isoSold: Option<String>,
+    pub isoStart: Option<String>,
+    pub gregorianYear: Option<u16>
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct RewardAndPunishStatus {
     pub r#type: String,
     pub times: u16
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct RewardAndPunishCollect {
     pub detail: Vec<RewardAndPunishDetailValue>,
     pub status: Vec<RewardAndPunishStatus>
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct RewardAndPunishData {
-    pub message: String,
+    #[schema(value_type = String)]
+    pub message: Message,
     pub data: RewardAndPunishCollect
 }
 
+// API: /getMeritBalance
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[allow(non_snake_case)]
+pub struct MeritBalanceCollect {
+    pub merit: u32,
+    pub demerit: u32,
+    pub cancelled: u32,
+    pub net: i32,
+    pub balance: Vec<RewardAndPunishStatus>,
+    pub expulsionRemain: u32,
+    pub nearExpulsion: bool,
+    pub reachExpulsion: bool,
+    pub cancellable: Vec<RewardAndPunishDetailValue>
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MeritBalanceData {
+    #[schema(value_type = String)]
+    pub message: Message,
+    pub data: MeritBalanceCollect
+}
+
 // API: /getLack
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[allow(non_snake_case)]
 pub struct LackRecordValue {
     pub data: Vec<Option<String>>,
     pub date: String,
+    pub isoDate: Option<String>,
     pub week: String
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct LackStatusValue {
     pub name: String,
     pub value: u16
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[allow(non_snake_case)]
 pub struct LackStatus {
     pub termDown: Vec<LackStatusValue>,
     pub termUp: Vec<LackStatusValue>
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct LackCollect {
     pub record: Vec<LackRecordValue>,
     pub total: LackStatus
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct LackData {
-    pub message: String,
+    #[schema(value_type = String)]
+    pub message: Message,
     pub data: LackCollect
 }
 
+// API: /getLack?typed=true
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema, Enum)]
+#[serde(rename_all = "camelCase")]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+    Unknown
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase", tag = "type", content = "number")]
+pub enum LackPeriod {
+    EarlyStudy,
+    FlagRaising,
+    Class(u8),
+    NoonBreak,
+    Cleaning,
+    Other
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema, Enum)]
+#[serde(rename_all = "camelCase")]
+pub enum AbsenceKind {
+    Absent,
+    Late,
+    EarlyLeave,
+    PersonalLeave,
+    SickLeave,
+    OfficialLeave,
+    BereavementLeave,
+    MenstrualLeave,
+    Other
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[allow(non_snake_case)]
+pub struct LackTypedRecord {
+    pub date: String,
+    pub isoDate: Option<String>,
+    pub weekday: Weekday,
+    pub period: LackPeriod,
+    pub periodLabel: String,
+    pub kind: AbsenceKind,
+    pub code: String
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LackTypedCollect {
+    pub record: Vec<LackTypedRecord>,
+    pub total: LackStatus
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LackTypedData {
+    #[schema(value_type = String)]
+    pub message: Message,
+    pub data: LackTypedCollect
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum LackDataResponse {
+    Raw(LackData),
+    Typed(LackTypedData)
+}
+
+// API: /getLackAnalytics
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LackCategoryCount {
+    pub name: String,
+    pub count: u16
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LackPeriodCount {
+    pub period: LackPeriod,
+    pub label: String,
+    pub count: u16
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[allow(non_snake_case)]
+pub struct LackMonthCount {
+    pub month: String,
+    pub isoMonth: Option<String>,
+    pub count: u16
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LackRuleLevel {
+    Normal,
+    Warning,
+    Exceeded
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LackRuleStatus {
+    pub name: String,
+    pub term: String,
+    pub value: u16,
+    pub warning: u16,
+    pub limit: u16,
+    pub remain: u16,
+    pub level: LackRuleLevel
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LackAnalyticsCollect {
+    pub categories: Vec<LackCategoryCount>,
+    pub periods: Vec<LackPeriodCount>,
+    pub months: Vec<LackMonthCount>,
+    pub rules: Vec<LackRuleStatus>
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LackAnalyticsData {
+    #[schema(value_type = String)]
+    pub message: Message,
+    pub data: LackAnalyticsCollect
+}
+
 // API: /getAllScores
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AllScoreNormalDataValue {
     pub name: String,
-    pub value: u8
+    pub value: f32
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AllScoreNormalData {
     pub name: String,
     pub values: Vec<AllScoreNormalDataValue>
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AllScoreTestDataInfo {
     pub name: String,
     pub term: u8,
@@ -264,55 +743,92 @@ pub struct AllScoreTestDataInfo {
     pub year: u8
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AllScoreTestDataValue {
     pub name: AllScoreTestDataInfo,
-    pub value: u8
+    pub value: f32
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AllScoreTestData {
     pub name: String,
     pub values: Vec<AllScoreTestDataValue>
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[allow(non_snake_case)]
 pub struct AllScoreTestCollect {
     pub dataNormal: Vec<AllScoreNormalData>,
     pub dataTest: Vec<AllScoreTestData>
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AllScoreData {
-    pub message: String,
+    #[schema(value_type = String)]
+    pub message: Message,
     pub data: AllScoreTestCollect
 }
 
 // API: /getScheduleList
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, SimpleObject)]
 pub struct ScheduleListValues {
     pub name: String,
     pub class: String,
     pub teacher: String
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ScheduleListCollect {
     pub schedules: Vec<ScheduleListValues>
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ScheduleListData {
-    pub message: String,
+    #[schema(value_type = String)]
+    pub message: Message,
     pub data: ScheduleListCollect
 }
 
 // API: /getSchedule
-// TODO:
-// Compete this api
-// #[derive(Debug, Serialize, Deserialize)]
-// pub struct Schedule
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[allow(non_snake_case)]
+pub struct ScheduleValue {
+    pub weekday: u8,
+    pub period: u8,
+    pub periodLabel: String,
+    pub subject: String,
+    pub teacher: Option<String>
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleCollect {
+    pub class: String,
+    pub teacher: String,
+    pub schedule: Vec<ScheduleValue>
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleData {
+    #[schema(value_type = String)]
+    pub message: Message,
+    pub data: ScheduleCollect
+}
+
+// API: /calendarFeed
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[allow(non_snake_case)]
+pub struct CalendarFeedCollect {
+    pub token: String,
+    pub scheduleUrl: String,
+    pub lackUrl: String
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CalendarFeedData {
+    #[schema(value_type = String)]
+    pub message: Message,
+    pub data: CalendarFeedCollect
+}
 
 // API: /shareScore
 #[derive(Debug, Serialize, Deserialize)]
@@ -325,7 +841,7 @@ pub struct ShareScoreCollect {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ShareScoreData {
-    pub message: String,
+    pub message: Message,
     pub data: ShareScoreCollect
 }
 
@@ -350,6 +866,6 @@ pub struct GetSharedCollect {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetSharedData {
-    pub message: String,
+    pub message: Message,
     pub data: GetSharedCollect
 }
\ No newline at end of file
@@ -1,15 +1,17 @@
-use std::{time::{SystemTime, UNIX_EPOCH}, env::{consts::OS, self}};
+use std::{time::{SystemTime, UNIX_EPOCH}, env::{consts::OS, self}, net::IpAddr};
 use lazy_static::lazy_static;
 use openssl::base64;
 use reqwest::{header::{HeaderMap, HeaderValue}, Response};
-use rocket::{response::status::Custom, serde::json::Json, http::Status};
+use rocket::{response::status::Custom, http::Status};
 use scraper::{Selector, Html, ElementRef};
-use url::form_urlencoded::Parse;
+use url::{form_urlencoded::Parse, Url, Host};
 
 use crate::{
+    responder::Json,
+    i18n::Message,
     types::{ErrorResponse, ResponseErrorAt, ErrorReturn},
     http::{APIPaths, HTTPErrorReturn, HTMLRespond, http_get_html, http_get},
-    error::HTTPError
+    error::{HTTPError, ErrorCode}
 };
 
 lazy_static! {
@@ -28,8 +30,6 @@ lazy_static! {
     };
 }
 
-pub const HELLO_MESSAGE: &str = "Hello from HLHSInfo Server!";
-
 pub fn get_timestamp() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -65,7 +65,7 @@ pub fn get_time_after_millisec(minute: u128) -> u128 {
     get_timestamp_millisec() + 60000 * minute
 }
 
-pub fn error_message(path: &str, code: Status, message: &str, at: Option<&str>) -> ErrorReturn {
+pub fn error_message(path: &str, status: Status, code: ErrorCode, message: Message, at: Option<&str>) -> ErrorReturn {
     let wrong = match at {
         Some(at) => Some(ResponseErrorAt {
             api: Some(String::from(path)),
@@ -75,13 +75,19 @@ pub fn error_message(path: &str, code: Status, message: &str, at: Option<&str>)
         None => None
     };
 
-    Custom(code, Json(ErrorResponse {
-        message: String::from(message),
+    Custom(status, Json(ErrorResponse {
+        code,
+        message,
         timestamp: get_timestamp_millisec(),
         wrong
     }))
 }
 
+// The same school number can exist on different school systems, so per user state is keyed by both.
+pub fn user_key(school_number: &str, host: &str) -> String {
+    format!("{}@{}", school_number, host)
+}
+
 pub fn combine_path(host: &str, path: &str) -> String {
     format!("{}{}", host, path)
 }
@@ -99,19 +105,76 @@ pub fn create_auth_header(cookie: &str) -> HeaderMap {
 
 pub fn generate_http_error(path: &str, err: HTTPErrorReturn) -> ErrorReturn {
     match err {
-        HTTPErrorReturn::RequestError(_) => return error_message(path, Status::ServiceUnavailable, HTTPError::RemoteServiceUnavailable.message(), Some("Remote server")),
-        HTTPErrorReturn::StatusCodeError(_) => return error_message(path, Status::BadGateway, HTTPError::ServerError.message(), Some("Return status code"))
+        HTTPErrorReturn::RequestError(_) => return error_message(path, Status::ServiceUnavailable, ErrorCode::UpstreamDown, HTTPError::RemoteServiceUnavailable.message(), Some("Remote server")),
+        HTTPErrorReturn::StatusCodeError(_) => return error_message(path, Status::BadGateway, ErrorCode::UpstreamError, HTTPError::ServerError.message(), Some("Return status code"))
     }
 }
 
 pub fn generate_session_expire_error(path: &str) -> ErrorReturn {
-    error_message(path, Status::Forbidden, HTTPError::SessionExpired.message(), None)
+    error_message(path, Status::Forbidden, HTTPError::SessionExpired.code(), HTTPError::SessionExpired.message(), None)
+}
+
+// The page from the school system is not in the expected layout.
+pub fn generate_parse_error(path: &str, at: &str) -> ErrorReturn {
+    error_message(path, Status::BadGateway, ErrorCode::ParseFailed, Message::ParseFailed, Some(at))
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => !(ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified() || ip.is_broadcast()
+            // Shared address space (RFC 6598)
+            || (ip.octets()[0] == 100 && ip.octets()[1] & 0xc0 == 64)),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ip(IpAddr::V4(ip)),
+            None => !(ip.is_loopback() || ip.is_unspecified()
+                // Unique local (fc00::/7) and link-local (fe80::/10)
+                || ip.segments()[0] & 0xfe00 == 0xfc00
+                || ip.segments()[0] & 0xffc0 == 0xfe80)
+        }
+    }
+}
+
+// Webhooks and push endpoints are called by the server, so they must be https and resolve only to public addresses.
+pub async fn is_public_https_url(url: &str) -> bool {
+    let url = match Url::parse(url) {
+        Ok(url) if url.scheme() == "https" => url,
+        _ => return false
+    };
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    match url.host() {
+        Some(Host::Ipv4(ip)) => is_public_ip(IpAddr::V4(ip)),
+        Some(Host::Ipv6(ip)) => is_public_ip(IpAddr::V6(ip)),
+        Some(Host::Domain(domain)) => match tokio::net::lookup_host((domain, port)).await {
+            Ok(addrs) => {
+                let addrs = addrs.collect::<Vec<_>>();
+                !addrs.is_empty() && addrs.iter().all(|addr| is_public_ip(addr.ip()))
+            },
+            Err(_) => false
+        },
+        None => false
+    }
 }
 
 pub fn buffer_to_base64(input: &[u8]) -> String {
 	base64::encode_block(input)
 }
 
+pub fn buffer_to_base64_url(input: &[u8]) -> String {
+    base64::encode_block(input)
+        .replace('+', "-")
+        .replace('/', "_")
+        .trim_end_matches('=')
+        .to_owned()
+}
+
+pub fn base64_url_to_buffer(input: &str) -> Option<Vec<u8>> {
+    let data = input.replace('-', "+").replace('_', "/");
+    let padding = "=".repeat((4 - data.len() % 4) % 4);
+
+    base64::decode_block(&format!("{}{}", data, padding)).ok()
+}
+
 pub fn is_document_logined(document: &Html) -> bool {
     let doc = document.select(&NOT_LOGIN_SELECTOR).next();
     
@@ -143,6 +206,29 @@ pub fn check_str_is_num(string: &str) -> bool {
     }
 }
 
+// Keep `start` chars at the beginning and `end` chars at the end, replace the rest with `*`.
+pub fn mask_string(string: &str, start: usize, end: usize) -> String {
+    let chars = string.chars().collect::<Vec<_>>();
+
+    if chars.len() <= start + end {
+        return "*".repeat(chars.len())
+    }
+
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, c)| if i < start || i >= chars.len() - end { *c } else { '*' })
+        .collect()
+}
+
+pub fn escape_xml(string: &str) -> String {
+    string
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 pub fn html_to_text(element: ElementRef<'_>) -> String {
     element
         .text()
@@ -0,0 +1,171 @@
+use std::{collections::{HashMap, HashSet}, sync::Mutex, time::Duration};
+use lazy_static::lazy_static;
+use rocket::{http::Status, tokio::{self, time::interval}};
+
+use crate::{
+    types::{AuthToken, AvailableScoreValue},
+    apis::v1::{get_available_score::get_available_score, get_score::get_score, get_lack::get_lack, get_reward_and_punish::get_reward_and_punish},
+    notify::{notify, create_event, EVENT_NEW_SCORE, EVENT_SCORE_OPENED, EVENT_NEW_LACK, EVENT_NEW_REWARD_AND_PUNISH},
+    config::read_config,
+    utils::{get_timestamp, user_key}
+};
+
+const WATCHER_PATH: &str = "watcher";
+
+#[derive(Clone)]
+pub struct WatchEntry {
+    pub token: AuthToken,
+    pub webhook: Option<String>,
+
+    initialized: bool,
+    known: HashSet<String>,
+    opened: HashSet<String>,
+    lack: HashSet<String>,
+    reward_and_punish: HashSet<String>
+}
+
+lazy_static! {
+    static ref WATCH_LIST: Mutex<HashMap<String, WatchEntry>> = Mutex::new(HashMap::new());
+}
+
+fn watch_key(token: &AuthToken) -> String {
+    user_key(&token.user_data.schoolNumber, &token.host)
+}
+
+// Watching again with a new session keeps what has already been seen.
+pub fn watch(token: AuthToken, webhook: Option<String>) {
+    let mut list = WATCH_LIST.lock().unwrap();
+    let key = watch_key(&token);
+
+    match list.get_mut(&key) {
+        Some(entry) => {
+            entry.token = token;
+            entry.webhook = webhook;
+        },
+        None => {
+            list.insert(key, WatchEntry {
+                token,
+                webhook,
+                initialized: false,
+                known: HashSet::new(),
+                opened: HashSet::new(),
+                lack: HashSet::new(),
+                reward_and_punish: HashSet::new()
+            });
+        }
+    }
+}
+
+pub fn unwatch(token: &AuthToken) -> bool {
+    WATCH_LIST.lock().unwrap().remove(&watch_key(token)).is_some()
+}
+
+async fn is_opened(token: &AuthToken, test: &AvailableScoreValue) -> bool {
+    get_score(WATCHER_PATH, token, &test.year.to_string(), &test.term.to_string(), &test.testID).await.is_ok()
+}
+
+// Returns false when the session could not be used anymore.
+async fn check_entry(entry: &mut WatchEntry) -> bool {
+    let list = match get_available_score(WATCHER_PATH, &entry.token).await {
+        Ok(list) => list,
+        Err(err) => return err.0 != Status::Forbidden
+    };
+
+    let school_number = entry.token.user_data.schoolNumber.clone();
+
+    for test in &list {
+        let is_new = entry.known.insert(test.testID.clone());
+
+        if is_new && entry.initialized {
+            notify(entry.webhook.as_deref(), &create_event(EVENT_NEW_SCORE, &school_number, &entry.token.host, test)).await;
+        }
+
+        if !entry.opened.contains(&test.testID) && is_opened(&entry.token, test).await {
+            entry.opened.insert(test.testID.clone());
+
+            if entry.initialized {
+                notify(entry.webhook.as_deref(), &create_event(EVENT_SCORE_OPENED, &school_number, &entry.token.host, test)).await;
+            }
+        }
+    }
+
+    if let Ok(lack) = get_lack(WATCHER_PATH, &entry.token).await {
+        for record in &lack.record {
+            let key = format!("{}|{:?}", record.date, record.data);
+
+            if entry.lack.insert(key) && entry.initialized {
+                notify(entry.webhook.as_deref(), &create_event(EVENT_NEW_LACK, &school_number, &entry.token.host, record)).await;
+            }
+        }
+    }
+
+    if let Ok(reward_and_punish) = get_reward_and_punish(WATCHER_PATH, &entry.token).await {
+        for detail in &reward_and_punish.detail {
+            let key = format!("{}|{}|{}|{}", detail.r#type, detail.start, detail.signed, detail.reason);
+
+            if entry.reward_and_punish.insert(key) && entry.initialized {
+                notify(entry.webhook.as_deref(), &create_event(EVENT_NEW_REWARD_AND_PUNISH, &school_number, &entry.token.host, detail)).await;
+            }
+        }
+    }
+
+    entry.initialized = true;
+
+    true
+}
+
+async fn check_all() {
+    let now = get_timestamp();
+    let entries = {
+        let mut list = WATCH_LIST.lock().unwrap();
+        list.retain(|_, entry| entry.token.exp > now);
+        list.iter().map(|(k, v)| (k.clone(), v.clone())).collect::<Vec<_>>()
+    };
+
+    // Each user runs in its own task, so a page that breaks a scraper only fails that user.
+    let tasks = entries
+        .into_iter()
+        .map(|(key, mut entry)| (key, tokio::spawn(async move {
+            let alive = check_entry(&mut entry).await;
+            (alive, entry)
+        })))
+        .collect::<Vec<_>>();
+
+    for (key, task) in tasks {
+        let (alive, entry) = match task.await {
+            Ok(result) => result,
+            Err(err) => {
+                println!("Watcher failed to check {}: {}", key, err);
+                continue
+            }
+        };
+        let mut list = WATCH_LIST.lock().unwrap();
+
+        if !alive {
+            list.remove(&key);
+        } else if let Some(stored) = list.get_mut(&key) {
+            stored.initialized = entry.initialized;
+            stored.known = entry.known;
+            stored.opened = entry.opened;
+            stored.lack = entry.lack;
+            stored.reward_and_punish = entry.reward_and_punish;
+        }
+    }
+}
+
+pub fn start_watcher() {
+    let config = read_config();
+
+    if !config.enable_record {
+        return
+    }
+
+    tokio::spawn(async move {
+        let mut timer = interval(Duration::from_secs(60 * config.check_cycle.max(1) as u64));
+
+        loop {
+            timer.tick().await;
+            check_all().await;
+        }
+    });
+}
\ No newline at end of file
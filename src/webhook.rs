@@ -0,0 +1,292 @@
+use std::{collections::{HashMap, HashSet}, fs::{File, OpenOptions, read_to_string}, io::prelude::*, sync::Mutex, time::Duration};
+use lazy_static::lazy_static;
+use openssl::{hash::MessageDigest, rand::rand_bytes};
+use reqwest::header::{HeaderMap, HeaderValue};
+use rocket::tokio::{self, time::interval};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    types::{NotifyEvent, WebhookValue},
+    http::http_post_json,
+    secure::create_hmac,
+    config::read_config,
+    utils::{DEFAULT_FILE_PATH, vecu8_to_hex_string, get_timestamp_millisec, is_public_https_url, user_key}
+};
+
+pub const WEBHOOK_FILE: &str = "webhooks.json";
+pub const QUEUE_FILE: &str = "webhook_queue.json";
+pub const DELIVERY_LOG_FILE: &str = "webhook_delivery.log";
+
+const QUEUE_CHECK_SECOND: u64 = 5;
+const DELIVERY_TIMEOUT_SECOND: u64 = 10;
+const MAX_RETRY_DELAY_SECOND: u128 = 24 * 60 * 60;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct WebhookRecord {
+    webhook: WebhookValue,
+    secret: String
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct WebhookDelivery {
+    id: String,
+    url: String,
+    secret: Option<String>,
+    event: NotifyEvent,
+    attempts: u8,
+    next_timestamp: u128
+}
+
+#[derive(Debug, Serialize)]
+struct DeliveryLog<'a> {
+    id: &'a str,
+    url: &'a str,
+    event: &'a str,
+    attempt: u8,
+    success: bool,
+    error: Option<String>,
+    timestamp: u128
+}
+
+lazy_static! {
+    static ref WEBHOOKS: Mutex<HashMap<String, Vec<WebhookRecord>>> = Mutex::new(read_json(WEBHOOK_FILE));
+    static ref QUEUE: Mutex<Vec<WebhookDelivery>> = Mutex::new(read_json(QUEUE_FILE));
+}
+
+fn read_json<T>(file: &str) -> T
+where
+    T: for<'de> Deserialize<'de> + Default
+{
+    match read_to_string(format!("{}/{}", *DEFAULT_FILE_PATH, file)) {
+        Ok(data) => serde_json::from_str(&data).unwrap_or_default(),
+        Err(_) => T::default()
+    }
+}
+
+fn write_json<T>(file: &str, data: &T)
+where
+    T: Serialize
+{
+    match File::create(format!("{}/{}", *DEFAULT_FILE_PATH, file)) {
+        Ok(f) => serde_json::to_writer(f, data).expect("Cannot write webhook data."),
+        Err(err) => println!("Cannot save {}: {}", file, err)
+    }
+}
+
+fn random_hex(len: usize) -> String {
+    let mut buffer = vec![0; len];
+    rand_bytes(&mut buffer).unwrap();
+
+    vecu8_to_hex_string(&buffer)
+}
+
+// Webhooks are kept per user key (schoolNumber@host).
+pub fn add_webhook(user_key: &str, url: &str, events: Vec<String>) -> (WebhookValue, String) {
+    let record = WebhookRecord {
+        webhook: WebhookValue {
+            id: random_hex(16),
+            url: url.to_owned(),
+            events,
+            createdTimestamp: get_timestamp_millisec()
+        },
+        secret: random_hex(32)
+    };
+
+    let mut webhooks = WEBHOOKS.lock().unwrap();
+    webhooks.entry(user_key.to_owned()).or_default().push(record.clone());
+    write_json(WEBHOOK_FILE, &*webhooks);
+
+    (record.webhook, record.secret)
+}
+
+pub fn list_webhooks(user_key: &str) -> Vec<WebhookValue> {
+    match WEBHOOKS.lock().unwrap().get(user_key) {
+        Some(list) => list.iter().map(|r| r.webhook.clone()).collect(),
+        None => Vec::new()
+    }
+}
+
+pub fn remove_webhook(user_key: &str, id: &str) -> bool {
+    let mut webhooks = WEBHOOKS.lock().unwrap();
+
+    let removed = match webhooks.get_mut(user_key) {
+        Some(list) => {
+            let len = list.len();
+            list.retain(|r| r.webhook.id != id);
+            len != list.len()
+        },
+        None => false
+    };
+
+    if removed {
+        write_json(WEBHOOK_FILE, &*webhooks);
+    }
+
+    removed
+}
+
+fn enqueue(url: &str, secret: Option<String>, event: &NotifyEvent) {
+    let mut queue = QUEUE.lock().unwrap();
+
+    queue.push(WebhookDelivery {
+        id: random_hex(16),
+        url: url.to_owned(),
+        secret,
+        event: event.clone(),
+        attempts: 0,
+        next_timestamp: get_timestamp_millisec()
+    });
+    write_json(QUEUE_FILE, &*queue);
+}
+
+// Queue the event for `url` (unsigned) and every webhook of the user which subscribed this event.
+pub fn dispatch(url: Option<&str>, event: &NotifyEvent) {
+    if let Some(url) = url {
+        enqueue(url, None, event);
+    }
+
+    let records = WEBHOOKS.lock().unwrap().get(&user_key(&event.schoolNumber, &event.host)).cloned().unwrap_or_default();
+
+    for record in records {
+        if record.webhook.events.contains(&event.event) {
+            enqueue(&record.webhook.url, Some(record.secret), event);
+        }
+    }
+}
+
+// The signature is `sha256=<hex>` of HMAC-SHA256("<timestamp>.<body>") with the webhook secret.
+pub fn sign_payload(secret: &str, timestamp: u128, body: &[u8]) -> String {
+    let hmac = create_hmac(MessageDigest::sha256(), secret.as_bytes(), &[timestamp.to_string().as_bytes(), b".", body]);
+
+    format!("sha256={}", vecu8_to_hex_string(&hmac))
+}
+
+// Returns the error text for the delivery log.
+async fn deliver(delivery: &WebhookDelivery) -> Result<(), String> {
+    // Checked again on delivery, the address of the host may have changed since it was registered.
+    if !is_public_https_url(&delivery.url).await {
+        return Err("Webhook url is not a public https address".to_owned())
+    }
+
+    let timestamp = get_timestamp_millisec();
+    let body = serde_json::to_vec(&delivery.event).unwrap();
+    let mut headers = HeaderMap::new();
+
+    headers.append("X-HLHSInfo-Event", HeaderValue::from_str(&delivery.event.event).unwrap());
+    headers.append("X-HLHSInfo-Delivery", HeaderValue::from_str(&delivery.id).unwrap());
+    headers.append("X-HLHSInfo-Timestamp", HeaderValue::from(timestamp as u64));
+
+    if let Some(secret) = &delivery.secret {
+        headers.append("X-HLHSInfo-Signature", HeaderValue::from_str(&sign_payload(secret, timestamp, &body)).unwrap());
+    }
+
+    http_post_json(&delivery.url, body, Some(headers), Duration::from_secs(DELIVERY_TIMEOUT_SECOND)).await
+        .map(|_| ())
+        .map_err(|err| format!("{:?}", err))
+}
+
+// Exponential backoff: delay, delay * 2, delay * 4, ... up to a day.
+fn retry_delay(base: u32, attempts: u8) -> u128 {
+    2u128.checked_pow(attempts.saturating_sub(1) as u32)
+        .map_or(MAX_RETRY_DELAY_SECOND, |factor| (base as u128).saturating_mul(factor))
+        .min(MAX_RETRY_DELAY_SECOND)
+}
+
+fn write_log(delivery: &WebhookDelivery, error: Option<String>) {
+    let log = DeliveryLog {
+        id: &delivery.id,
+        url: &delivery.url,
+        event: &delivery.event.event,
+        attempt: delivery.attempts,
+        success: error.is_none(),
+        error,
+        timestamp: get_timestamp_millisec()
+    };
+
+    let path = format!("{}/{}", *DEFAULT_FILE_PATH, DELIVERY_LOG_FILE);
+    match OpenOptions::new().create(true).append(true).open(path) {
+        Ok(mut file) => {
+            let _ = writeln!(file, "{}", serde_json::to_string(&log).unwrap());
+        },
+        Err(err) => println!("Cannot write webhook delivery log: {}", err)
+    }
+}
+
+async fn process_queue() {
+    let now = get_timestamp_millisec();
+
+    // Due deliveries stay in the queue file until they are delivered or dropped, so they are sent again after a restart.
+    let due = {
+        let mut queue = QUEUE.lock().unwrap();
+        let due = queue
+            .iter_mut()
+            .filter(|d| d.next_timestamp <= now)
+            .map(|d| {
+                d.attempts = d.attempts.saturating_add(1);
+                d.clone()
+            })
+            .collect::<Vec<_>>();
+
+        if !due.is_empty() {
+            write_json(QUEUE_FILE, &*queue);
+        }
+
+        due
+    };
+
+    if due.is_empty() {
+        return
+    }
+
+    let config = read_config();
+
+    // Every delivery runs in its own task, so a slow endpoint does not hold back the others.
+    let tasks = due
+        .into_iter()
+        .map(|delivery| {
+            let task = delivery.clone();
+            (delivery, tokio::spawn(async move { deliver(&task).await }))
+        })
+        .collect::<Vec<_>>();
+
+    let mut finished: HashSet<String> = HashSet::new();
+    let mut retry: HashMap<String, u128> = HashMap::new();
+
+    for (delivery, task) in tasks {
+        let error = match task.await {
+            Ok(result) => result.err(),
+            Err(err) => Some(err.to_string())
+        };
+
+        write_log(&delivery, error.clone());
+
+        if error.is_some() && delivery.attempts <= config.webhook_max_retries {
+            let delay = retry_delay(config.webhook_retry_delay, delivery.attempts);
+            retry.insert(delivery.id, get_timestamp_millisec() + delay * 1000);
+        } else {
+            finished.insert(delivery.id);
+        }
+    }
+
+    let mut queue = QUEUE.lock().unwrap();
+    queue.retain(|d| !finished.contains(&d.id));
+
+    for delivery in queue.iter_mut() {
+        if let Some(next_timestamp) = retry.get(&delivery.id) {
+            delivery.next_timestamp = *next_timestamp;
+        }
+    }
+
+    write_json(QUEUE_FILE, &*queue);
+}
+
+pub fn start_delivery() {
+    tokio::spawn(async move {
+        let mut timer = interval(Duration::from_secs(QUEUE_CHECK_SECOND));
+
+        loop {
+            timer.tick().await;
+            process_queue().await;
+        }
+    });
+}
\ No newline at end of file
@@ -0,0 +1,57 @@
+use hlhsinfo_backend_server::{
+    apis::v1::get_score_analytics::{analyze_test, analyze_subjects},
+    types::{AvailableScoreValue, ScoreDataCollect, ScoreDataValue, ScoreUnpass, ScoreValue}
+};
+
+fn exam(times: u8) -> AvailableScoreValue {
+    AvailableScoreValue {
+        name: format!("第{}次段考", times),
+        term: 1,
+        testID: format!("110{}", times),
+        times,
+        r#type: 1,
+        year: 110,
+        gregorianYear: Some(2021)
+    }
+}
+
+fn subject(name: &str, raw: &str) -> ScoreDataValue {
+    let value = ScoreValue::parse(raw);
+
+    ScoreDataValue {
+        name: name.to_owned(),
+        score: value.number().map_or(0, |v| v.round() as u8),
+        gpa: 0.0,
+        scoreValue: value,
+        gpaValue: ScoreValue::parse("")
+    }
+}
+
+fn scores(data: Vec<ScoreDataValue>, failed: &[&str]) -> ScoreDataCollect {
+    ScoreDataCollect {
+        data,
+        extra: Vec::new(),
+        unpass: failed.iter().map(|name| ScoreUnpass { name: name.to_string(), r#type: "score".to_owned() }).collect()
+    }
+}
+
+#[test]
+fn only_graded_subjects_pass_or_fail() {
+    let score = scores(vec![subject("國文", "80"), subject("數學", "45.5"), subject("體育", "缺考"), subject("美術", "免修")], &["數學"]);
+    let test = analyze_test(&exam(1), &score);
+
+    assert_eq!((test.pass, test.fail), (1, 1));
+    assert_eq!(test.average, 62.75);
+}
+
+#[test]
+fn trends_keep_the_decimals() {
+    let list = vec![
+        (exam(1), scores(vec![subject("數學", "60.5")], &[])),
+        (exam(2), scores(vec![subject("數學", "72.25")], &[]))
+    ];
+    let trend = &analyze_subjects(&list)[0];
+
+    assert_eq!(trend.points.iter().map(|p| p.score).collect::<Vec<_>>(), [60.5, 72.25]);
+    assert_eq!(trend.change, Some(11.75));
+}
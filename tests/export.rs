@@ -0,0 +1,72 @@
+use hlhsinfo_backend_server::{
+    apis::v1::get_all_scores::parse_all_scores,
+    export::{ExportFormat, ExportSheet, ExportCell, all_score_to_sheets, export}
+};
+
+const ALL_SCORES_PAGE: &str = r#"<html><body>
+<div id="chart1"></div><div id="chart2"></div>
+<script type="text/javascript">
+$('#chart1').highcharts({
+    title: { text: '學期成績' },
+    xAxis: { categories: ['一上', '一下', '二上'] },
+    series: [{ name: '國文', data: [80, 85.5, null] }, { name: "數學", data: [60, 72, 90] }]
+});
+$('#chart2').highcharts({
+    title: { text: '段考成績' },
+    xAxis: { categories: ['110學年第1學期第1次段考', '110學年第1學期第2次段考'] },
+    series: [{ name: '國文', data: [77, 91] }]
+});
+</script>
+</body></html>"#;
+
+#[test]
+fn all_scores_are_read_from_the_charts() {
+    let data = parse_all_scores(ALL_SCORES_PAGE).unwrap();
+
+    assert_eq!(data.dataNormal.len(), 2);
+    assert_eq!(data.dataNormal[0].name, "國文");
+    assert_eq!(data.dataNormal[0].values.iter().map(|v| (v.name.as_str(), v.value)).collect::<Vec<_>>(), [("一上", 80.0), ("一下", 85.5)]);
+    assert_eq!(data.dataNormal[1].values.len(), 3);
+
+    assert_eq!(data.dataTest.len(), 1);
+    let test = &data.dataTest[0].values[1];
+    assert_eq!((test.name.year, test.name.term, test.name.test, test.value), (110, 1, 2, 91.0));
+
+    assert_eq!(all_score_to_sheets(&data).iter().map(|s| s.rows.len()).collect::<Vec<_>>(), [6, 3]);
+}
+
+#[test]
+fn page_without_charts_is_a_parse_error() {
+    assert!(parse_all_scores("<html><body>尚未開放</body></html>").is_none());
+}
+
+#[test]
+fn csv_cells_are_not_formulas() {
+    let sheet = ExportSheet {
+        name: "Sheet".to_owned(),
+        rows: vec![vec![
+            ExportCell::from("=HYPERLINK(\"http://example.com\")"),
+            ExportCell::from("+1"),
+            ExportCell::from("-1"),
+            ExportCell::from("@SUM(A1)"),
+            ExportCell::from("normal"),
+            ExportCell::Number(-1.5)
+        ]]
+    };
+
+    let csv = String::from_utf8(export(&[sheet], ExportFormat::Csv).unwrap()).unwrap();
+
+    assert_eq!(csv, "\u{feff}\"'=HYPERLINK(\"\"http://example.com\"\")\",'+1,'-1,'@SUM(A1),normal,-1.5\r\n");
+}
+
+#[test]
+fn xlsx_is_a_zip_package() {
+    let sheet = ExportSheet {
+        name: "Score: [1/2]".to_owned(),
+        rows: vec![vec![ExportCell::from("科目"), ExportCell::Number(90.0)]]
+    };
+
+    let xlsx = export(&[sheet], ExportFormat::Xlsx).unwrap();
+
+    assert_eq!(&xlsx[0..4], b"PK\x03\x04");
+}
@@ -0,0 +1,82 @@
+use async_graphql::{Request, Response};
+use hlhsinfo_backend_server::{
+    graphql::{execute, MAX_QUERY_COMPLEXITY, SCORE_COMPLEXITY},
+    types::{AuthToken, UserProfileShortValue}
+};
+use serde_json::json;
+
+// Fields which only read the token never contact the school server.
+fn token() -> AuthToken {
+    AuthToken {
+        host: "http://127.0.0.1:9".to_owned(),
+        cookie: String::new(),
+        user_data: UserProfileShortValue {
+            className: "一年一班".to_owned(),
+            classNumber: "01".to_owned(),
+            gender: "男".to_owned(),
+            schoolNumber: "123456".to_owned(),
+            userName: "王小明".to_owned()
+        },
+        iat: 0,
+        exp: u64::MAX
+    }
+}
+
+async fn run(query: &str) -> Response {
+    execute(Request::new(query), token()).await
+}
+
+fn error_messages(response: &Response) -> Vec<String> {
+    response.errors.iter().map(|e| e.message.clone()).collect()
+}
+
+#[tokio::test]
+async fn user_is_read_from_the_token() {
+    let response = run("{ user { name className schoolNumber } }").await;
+
+    assert!(response.errors.is_empty(), "{:?}", error_messages(&response));
+    assert_eq!(response.data.into_json().unwrap(), json!({
+        "user": { "name": "王小明", "className": "一年一班", "schoolNumber": "123456" }
+    }));
+}
+
+#[tokio::test]
+async fn schedule_needs_both_class_and_teacher() {
+    let response = run(r#"{ schedule(class: "101") { class } }"#).await;
+    let error = serde_json::to_value(&response.errors[0]).unwrap();
+
+    assert_eq!(error["extensions"]["code"], "MISSING_ARGUMENT");
+}
+
+#[tokio::test]
+async fn introspection_of_graphiql_is_allowed() {
+    let type_ref = (0..7).fold("kind name".to_owned(), |inner, _| format!("kind name ofType {{ {} }}", inner));
+    let query = format!(
+        "{{ __schema {{ queryType {{ name }} types {{ kind name description fields(includeDeprecated: true) {{ name args {{ name type {{ {0} }} }} type {{ {0} }} }} inputFields {{ name type {{ {0} }} }} enumValues(includeDeprecated: true) {{ name }} possibleTypes {{ {0} }} }} }} }}",
+        type_ref
+    );
+
+    let response = run(&query).await;
+
+    assert!(response.errors.is_empty(), "{:?}", error_messages(&response));
+}
+
+#[tokio::test]
+async fn deep_query_is_rejected() {
+    let type_ref = (0..20).fold("name".to_owned(), |inner, _| format!("ofType {{ {} }}", inner));
+    let response = run(&format!("{{ __schema {{ types {{ fields {{ type {{ {} }} }} }} }} }}", type_ref)).await;
+
+    assert!(error_messages(&response).iter().any(|m| m.contains("nested too deep")), "{:?}", error_messages(&response));
+}
+
+#[tokio::test]
+async fn query_fetching_many_scores_is_rejected() {
+    let fields = (0..=MAX_QUERY_COMPLEXITY / SCORE_COMPLEXITY)
+        .map(|i| format!(r#"e{}: examScore(id: "{}", year: 110, term: 1) {{ subjects {{ name }} }}"#, i, i))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let response = run(&format!("{{ {} }}", fields)).await;
+
+    assert!(error_messages(&response).iter().any(|m| m.contains("too complex")), "{:?}", error_messages(&response));
+    assert!(response.data.into_json().unwrap().is_null());
+}
@@ -0,0 +1,56 @@
+use hlhsinfo_backend_server::{
+    routes::create_server,
+    i18n::{Message, negotiate, with_locale},
+    error::ErrorCode,
+    utils::error_message
+};
+use rocket::{http::{Header, Status}, local::blocking::Client};
+use serde_json::Value;
+
+fn get(path: &str, accept_language: Option<&str>) -> (Status, Option<String>, Value) {
+    let client = Client::untracked(create_server(rocket::Config::figment())).unwrap();
+    let mut request = client.get(path);
+    if let Some(accept_language) = accept_language {
+        request = request.header(Header::new("Accept-Language", accept_language.to_owned()));
+    }
+
+    let response = request.dispatch();
+    let status = response.status();
+    let language = response.headers().get_one("Content-Language").map(str::to_owned);
+
+    (status, language, response.into_json().unwrap())
+}
+
+#[test]
+fn message_is_written_in_the_requested_locale() {
+    let (_, language, body) = get("/", Some("zh-HK, en;q=0.5"));
+
+    assert_eq!(language.as_deref(), Some("zh-TW"));
+    assert_eq!(body["message"], "HLHSInfo 伺服器運作中！");
+}
+
+#[test]
+fn message_is_english_without_accept_language() {
+    let (_, language, body) = get("/", None);
+
+    assert_eq!(language, None);
+    assert_eq!(body["message"], Message::Hello.text());
+}
+
+#[test]
+fn error_message_is_translated_by_its_id() {
+    let (status, _, body) = get("/v1/notExist", Some("zh-TW"));
+
+    assert_eq!(status, Status::NotFound);
+    assert_eq!(body["code"], "NOT_FOUND");
+    assert_eq!(body["message"], "找不到此 API，請檢查 API 路徑。");
+}
+
+#[test]
+fn same_text_with_another_id_is_kept_apart() {
+    let error = error_message("/v1/test", Status::BadRequest, ErrorCode::InvalidArgument, Message::WrongArguments, None).1.into_inner();
+    let body = with_locale(negotiate("zh-TW"), || serde_json::to_value(&error)).unwrap();
+
+    assert_eq!(body["message"], "參數錯誤");
+    assert_eq!(serde_json::to_value(&error).unwrap()["message"], "Wrong arguments");
+}
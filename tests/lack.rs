@@ -0,0 +1,51 @@
+use hlhsinfo_backend_server::{
+    apis::v1::{get_lack::decode_period, get_lack_analytics::count_records},
+    types::{LackPeriod, LackRecordValue}
+};
+
+#[test]
+fn chinese_numerals_are_read_whole() {
+    assert_eq!(decode_period("一"), LackPeriod::Class(1));
+    assert_eq!(decode_period("第八節"), LackPeriod::Class(8));
+    assert_eq!(decode_period("十"), LackPeriod::Class(10));
+    assert_eq!(decode_period("十一"), LackPeriod::Class(11));
+    assert_eq!(decode_period("第十二節"), LackPeriod::Class(12));
+    assert_eq!(decode_period("二十"), LackPeriod::Class(20));
+}
+
+#[test]
+fn digits_and_named_periods() {
+    assert_eq!(decode_period("3"), LackPeriod::Class(3));
+    assert_eq!(decode_period("第11節"), LackPeriod::Class(11));
+    assert_eq!(decode_period("早修"), LackPeriod::EarlyStudy);
+    assert_eq!(decode_period("午休"), LackPeriod::NoonBreak);
+    assert_eq!(decode_period("降旗"), LackPeriod::Other);
+}
+
+#[test]
+fn analytics_counts_periods_by_header_label() {
+    let header = ["早修", "升旗", "一", "二", "三", "四", "午休", "五", "六", "七", "八"].map(String::from);
+    let row = |date: &str, cells: &[(usize, &str)]| {
+        let mut data = vec![None; header.len()];
+        for (index, code) in cells {
+            data[*index] = Some(code.to_string());
+        }
+
+        LackRecordValue { data, date: date.to_owned(), isoDate: None, week: "一".to_owned() }
+    };
+    let records = [
+        row("112/09/04", &[(0, "遲"), (2, "曠"), (7, "曠")]),
+        row("112/10/02", &[(7, "病")])
+    ];
+
+    let (categories, periods, months) = count_records(&records, &header);
+
+    let periods = periods.iter().map(|p| (p.period, p.label.as_str(), p.count)).collect::<Vec<_>>();
+    assert_eq!(periods, [
+        (LackPeriod::EarlyStudy, "早修", 1),
+        (LackPeriod::Class(1), "一", 1),
+        (LackPeriod::Class(5), "五", 2)
+    ]);
+    assert_eq!(categories.iter().find(|c| c.name == "曠").map(|c| c.count), Some(2));
+    assert_eq!(months.iter().map(|m| m.count).collect::<Vec<_>>(), [3, 1]);
+}
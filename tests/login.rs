@@ -0,0 +1,52 @@
+use std::net::IpAddr;
+
+use hlhsinfo_backend_server::{
+    apis::v1::login::{detect_login_failure, get_alert_message, LoginFailure},
+    request_handler::resolve_client_ip
+};
+
+// The login form is on every answered page, with its labels.
+fn login_page(script: &str) -> String {
+    format!(r#"<html><head><title>學生資訊系統</title></head><body>
+<form method="post" action="/auth/Auth/DoCloudLoginCheck">
+    <label for="Loginid">帳號</label><input id="Loginid" name="Loginid" type="text" />
+    <label for="LoginPwd">密碼</label><input id="LoginPwd" name="LoginPwd" type="password" />
+    <label for="vcode">驗證碼</label><input id="vcode" name="vcode" type="text" />
+</form>
+<script type="text/javascript">{}</script>
+</body></html>"#, script)
+}
+
+#[test]
+fn alert_message_ends_at_its_quote() {
+    assert_eq!(get_alert_message(&login_page("alert('帳號或密碼錯誤(剩餘 2 次)');")), Some("帳號或密碼錯誤(剩餘 2 次)"));
+    assert_eq!(get_alert_message(&login_page(r#"alert( "驗證碼輸入錯誤" ); location.href = '/';"#)), Some("驗證碼輸入錯誤"));
+    assert_eq!(get_alert_message(&login_page(r"alert('It\'s locked');")), Some(r"It\'s locked"));
+    assert_eq!(get_alert_message(&login_page("")), None);
+}
+
+#[test]
+fn failure_is_read_from_the_alert() {
+    assert_eq!(detect_login_failure(&login_page("alert('驗證碼輸入錯誤');")), LoginFailure::CaptchaWrong);
+    assert_eq!(detect_login_failure(&login_page("alert('帳號已被鎖定(請 30 分鐘後再試)');")), LoginFailure::AccountLocked);
+    assert_eq!(detect_login_failure(&login_page("alert('此帳號已停用，請洽註冊組');")), LoginFailure::AccountLocked);
+    assert_eq!(detect_login_failure(&login_page("alert('帳號或密碼錯誤');")), LoginFailure::CredentialsWrong);
+    assert_eq!(detect_login_failure(&login_page("alert('系統維護中');")), LoginFailure::Unknown);
+}
+
+#[test]
+fn form_labels_are_not_a_failure_reason() {
+    assert_eq!(detect_login_failure(&login_page("")), LoginFailure::Unknown);
+    assert_eq!(detect_login_failure(&login_page("console.log('ready');")), LoginFailure::Unknown);
+}
+
+#[test]
+fn ip_header_is_only_trusted_from_a_proxy() {
+    let ip = |s: &str| s.parse::<IpAddr>().ok();
+    let proxy = ip("10.0.0.2").unwrap();
+
+    assert_eq!(resolve_client_ip(ip("203.0.113.7"), ip("198.51.100.1"), &[proxy]), ip("203.0.113.7"));
+    assert_eq!(resolve_client_ip(ip("203.0.113.7"), ip("198.51.100.1"), &[]), ip("203.0.113.7"));
+    assert_eq!(resolve_client_ip(Some(proxy), ip("198.51.100.1"), &[proxy]), ip("198.51.100.1"));
+    assert_eq!(resolve_client_ip(Some(proxy), None, &[proxy]), Some(proxy));
+}
@@ -0,0 +1,109 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use hlhsinfo_backend_server::{routes::create_server, apis::v1::openapi::ApiDoc};
+use serde_json::Value;
+use utoipa::OpenApi;
+
+// Routes that are not part of the api itself.
+const UNDOCUMENTED: [&str; 3] = ["/v1", "/v1/openapi.json", "/v1/docs"];
+
+// "GET /v1/getLack" -> query parameters, `None` when the route takes a whole form (`<params..>`).
+fn mounted_routes() -> BTreeMap<String, Option<BTreeSet<String>>> {
+    create_server(rocket::Config::figment())
+        .routes()
+        .filter_map(|route| {
+            let uri = route.uri.to_string();
+            let (path, query) = uri.split_once('?').unwrap_or((&uri, ""));
+            if !path.starts_with("/v1") || UNDOCUMENTED.contains(&path) {
+                return None
+            }
+
+            let path = path.replace('<', "{").replace('>', "}");
+            let params = query
+                .split('&')
+                .filter(|q| !q.is_empty())
+                .map(|q| q.trim_start_matches('<').trim_end_matches('>').to_owned())
+                .collect::<BTreeSet<_>>();
+            let params = (!params.iter().any(|p| p.ends_with(".."))).then_some(params);
+
+            Some((format!("{} {}", route.method.as_str(), path), params))
+        })
+        .collect()
+}
+
+fn documented_routes(spec: &Value) -> BTreeMap<String, BTreeSet<String>> {
+    spec["paths"]
+        .as_object()
+        .unwrap()
+        .iter()
+        .flat_map(|(path, item)| item.as_object().unwrap().iter().map(move |(method, operation)| {
+            let params = operation["parameters"]
+                .as_array()
+                .map(|params| params
+                    .iter()
+                    .filter(|p| p["in"] == "query")
+                    .map(|p| p["name"].as_str().unwrap().to_owned())
+                    .collect())
+                .unwrap_or_default();
+
+            (format!("{} {}", method.to_uppercase(), path), params)
+        }))
+        .collect()
+}
+
+fn collect_refs(value: &Value, refs: &mut BTreeSet<String>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(r)) = map.get("$ref") {
+                refs.insert(r.clone());
+            }
+            map.values().for_each(|v| collect_refs(v, refs));
+        },
+        Value::Array(list) => list.iter().for_each(|v| collect_refs(v, refs)),
+        _ => {}
+    }
+}
+
+#[test]
+fn every_route_is_documented() {
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let mounted = mounted_routes();
+    let documented = documented_routes(&spec);
+
+    let missing = mounted.keys().filter(|r| !documented.contains_key(*r)).collect::<Vec<_>>();
+    let removed = documented.keys().filter(|r| !mounted.contains_key(*r)).collect::<Vec<_>>();
+    assert!(missing.is_empty(), "Routes missing in the OpenAPI document: {:?}", missing);
+    assert!(removed.is_empty(), "Documented routes that are not mounted: {:?}", removed);
+
+    for (route, params) in &mounted {
+        if let Some(params) = params {
+            assert_eq!(params, &documented[route], "Query parameters of {} differ", route);
+        }
+    }
+}
+
+#[test]
+fn every_schema_is_registered() {
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let mut refs = BTreeSet::new();
+    collect_refs(&spec, &mut refs);
+
+    let unknown = refs
+        .iter()
+        .filter(|r| spec.pointer(r.trim_start_matches('#')).is_none())
+        .collect::<Vec<_>>();
+    assert!(unknown.is_empty(), "Schemas missing in the OpenAPI document: {:?}", unknown);
+}
+
+#[test]
+fn operation_ids_are_unique() {
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let mut seen = BTreeSet::new();
+
+    for item in spec["paths"].as_object().unwrap().values() {
+        for operation in item.as_object().unwrap().values() {
+            let id = operation["operationId"].as_str().unwrap();
+            assert!(seen.insert(id.to_owned()), "Duplicated operationId: {}", id);
+        }
+    }
+}
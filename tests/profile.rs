@@ -0,0 +1,46 @@
+use hlhsinfo_backend_server::{
+    apis::v1::get_user_profile::{to_user_profile, get_image_id},
+    mask::mask_user_datas,
+    config::default_mask_fields,
+    types::{UserDataValues, MaskFieldConfig, MaskPolicy}
+};
+use rocket::http::Status;
+
+fn datas() -> Vec<UserDataValues> {
+    [("身分證字號", "A123456789"), ("出生日期", "2008/01/02"), ("住址", "花蓮市")]
+        .iter()
+        .map(|(name, value)| UserDataValues { name: name.to_string(), value: value.to_string() })
+        .collect()
+}
+
+#[test]
+fn typed_id_number_is_masked_once_by_config() {
+    let profile = to_user_profile(mask_user_datas(datas(), &default_mask_fields()));
+
+    assert_eq!(profile.idNumber.as_deref(), Some("A*******89"));
+    assert_eq!(profile.birthday.as_deref(), Some("2008/01/02"));
+    assert_eq!(profile.address.as_deref(), Some("***"));
+}
+
+#[test]
+fn typed_id_number_follows_the_configured_policy() {
+    let fields = [MaskFieldConfig { name: "身分證".to_owned(), policy: MaskPolicy::Redact, keep_start: 0, keep_end: 0 }];
+
+    assert_eq!(to_user_profile(mask_user_datas(datas(), &fields)).idNumber.as_deref(), Some("***"));
+    assert_eq!(to_user_profile(datas()).idNumber.as_deref(), Some("A123456789"));
+}
+
+#[test]
+fn image_id_is_read_from_the_profile_image() {
+    let page = r#"<html><body><img src="../../auth/ImageHandler.ashx?id=ABC123&amp;t=1" /></body></html>"#;
+
+    assert_eq!(get_image_id("/v1/getProfileImage", page).unwrap(), "ABC123");
+}
+
+#[test]
+fn page_without_image_is_a_parse_error() {
+    match get_image_id("/v1/getProfileImage", "<html><body>請重新登入</body></html>") {
+        Err(err) => assert_eq!(err.0, Status::BadGateway),
+        Ok(id) => panic!("unexpected id {}", id)
+    }
+}
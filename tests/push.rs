@@ -0,0 +1,169 @@
+use std::{io::{BufRead, BufReader, Read, Write}, net::TcpListener, sync::mpsc, thread};
+
+use hlhsinfo_backend_server::{
+    push::{send_push, get_vapid_public_key, add_subscription, list_subscriptions, remove_subscription},
+    types::{PushSubscriptionValue, PushSubscriptionKeys, VapidClaims},
+    secure::create_hmac,
+    utils::{buffer_to_base64_url, base64_url_to_buffer, user_key}
+};
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use openssl::{
+    bn::BigNumContext,
+    derive::Deriver,
+    ec::{EcGroup, EcKey, EcPoint, PointConversionForm},
+    hash::MessageDigest,
+    nid::Nid,
+    pkey::PKey,
+    rand::rand_bytes,
+    symm::{decrypt_aead, Cipher}
+};
+
+struct ReceivedPush {
+    headers: Vec<(String, String)>,
+    body: Vec<u8>
+}
+
+impl ReceivedPush {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+    }
+}
+
+// A stand-in push service, which accepts one request and answers 201 Created.
+fn start_push_service() -> (String, mpsc::Receiver<ReceivedPush>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}/push/subscription-id", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut headers = Vec::new();
+
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end();
+
+            if line.is_empty() {
+                break
+            }
+            if let Some((key, value)) = line.split_once(':') {
+                headers.push((key.trim().to_owned(), value.trim().to_owned()));
+            }
+        }
+
+        let length = headers.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case("Content-Length"))
+            .map(|(_, value)| value.parse::<usize>().unwrap())
+            .unwrap_or(0);
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+
+        stream.write_all(b"HTTP/1.1 201 Created\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").unwrap();
+        sender.send(ReceivedPush { headers, body }).unwrap();
+    });
+
+    (endpoint, receiver)
+}
+
+// The keys a browser would create for its subscription.
+fn create_user_agent_keys() -> (EcKey<openssl::pkey::Private>, Vec<u8>, PushSubscriptionKeys) {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+    let key = EcKey::generate(&group).unwrap();
+    let public = key.public_key().to_bytes(&group, PointConversionForm::UNCOMPRESSED, &mut BigNumContext::new().unwrap()).unwrap();
+
+    let mut auth = [0; 16];
+    rand_bytes(&mut auth).unwrap();
+
+    let keys = PushSubscriptionKeys {
+        p256dh: buffer_to_base64_url(&public),
+        auth: buffer_to_base64_url(&auth)
+    };
+
+    (key, auth.to_vec(), keys)
+}
+
+// Decrypt an "aes128gcm" body as the user agent (RFC 8291 and RFC 8188).
+fn decrypt_payload(key: &EcKey<openssl::pkey::Private>, auth: &[u8], body: &[u8]) -> Vec<u8> {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+    let mut ctx = BigNumContext::new().unwrap();
+
+    let salt = &body[0..16];
+    let id_length = body[20] as usize;
+    let as_public = &body[21..21 + id_length];
+    let (cipher, tag) = body[21 + id_length..].split_at(body.len() - 21 - id_length - 16);
+
+    let ua_public = key.public_key().to_bytes(&group, PointConversionForm::UNCOMPRESSED, &mut ctx).unwrap();
+    let as_point = EcPoint::from_bytes(&group, as_public, &mut ctx).unwrap();
+    let as_key = PKey::from_ec_key(EcKey::from_public_key(&group, &as_point).unwrap()).unwrap();
+
+    let ua_key = PKey::from_ec_key(key.clone()).unwrap();
+    let mut deriver = Deriver::new(&ua_key).unwrap();
+    deriver.set_peer(&as_key).unwrap();
+    let ecdh_secret = deriver.derive_to_vec().unwrap();
+
+    let prk_key = create_hmac(MessageDigest::sha256(), auth, &[&ecdh_secret]);
+    let ikm = create_hmac(MessageDigest::sha256(), &prk_key, &[b"WebPush: info\0", &ua_public, as_public, &[1]]);
+    let prk = create_hmac(MessageDigest::sha256(), salt, &[&ikm]);
+    let cek = create_hmac(MessageDigest::sha256(), &prk, &[b"Content-Encoding: aes128gcm\0", &[1]]);
+    let nonce = create_hmac(MessageDigest::sha256(), &prk, &[b"Content-Encoding: nonce\0", &[1]]);
+
+    let mut plain = decrypt_aead(Cipher::aes_128_gcm(), &cek[0..16], Some(&nonce[0..12]), &[], cipher, tag).unwrap();
+
+    // The last record ends with the 0x02 delimiter.
+    assert_eq!(plain.pop(), Some(2));
+
+    plain
+}
+
+#[tokio::test]
+async fn push_is_signed_and_decryptable() {
+    let (endpoint, receiver) = start_push_service();
+    let (ua_key, auth, keys) = create_user_agent_keys();
+    let payload = br#"{"event":"score.new","schoolNumber":"123456"}"#;
+
+    let subscription = PushSubscriptionValue {
+        id: String::from("test"),
+        endpoint: endpoint.clone(),
+        keys,
+        createdTimestamp: 0
+    };
+
+    send_push(&subscription, payload).await.unwrap();
+    let push = receiver.recv().unwrap();
+
+    assert_eq!(push.header("Content-Encoding"), Some("aes128gcm"));
+    assert!(push.header("TTL").is_some());
+
+    // Authorization: vapid t=<jwt>, k=<public key>
+    let authorization = push.header("Authorization").unwrap();
+    let (token, public_key) = authorization
+        .strip_prefix("vapid t=")
+        .and_then(|value| value.split_once(", k="))
+        .unwrap();
+
+    assert_eq!(public_key, get_vapid_public_key().unwrap());
+
+    let public_key = base64_url_to_buffer(public_key).unwrap();
+    let decoding_key = DecodingKey::from_ec_components(&buffer_to_base64_url(&public_key[1..33]), &buffer_to_base64_url(&public_key[33..65])).unwrap();
+    let claims = decode::<VapidClaims>(token, &decoding_key, &Validation::new(Algorithm::ES256)).unwrap().claims;
+
+    assert_eq!(claims.aud, url::Url::parse(&endpoint).unwrap().origin().ascii_serialization());
+    assert!(claims.sub.starts_with("mailto:") || claims.sub.starts_with("https:"));
+
+    assert_eq!(decrypt_payload(&ua_key, &auth, &push.body), payload);
+}
+
+#[test]
+fn subscriptions_are_kept_apart_per_host() {
+    let (_, _, keys) = create_user_agent_keys();
+    let first = user_key("654321", "https://first.example");
+    let second = user_key("654321", "https://second.example");
+
+    let subscription = add_subscription(&first, "https://push.example/first", keys);
+
+    assert!(list_subscriptions(&second).is_empty());
+    assert!(!remove_subscription(&second, &subscription.id));
+    assert!(remove_subscription(&first, &subscription.id));
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use hlhsinfo_backend_server::{
+    transcript::{create_transcript_pages, render_transcript},
+    pdf::{create_pdf, PdfDocument, PdfError, PdfFont},
+    image_render::{render_svg_to_png, BUNDLED_FONTS},
+    types::{UserProfileShortValue, AllScoreTestCollect, AllScoreNormalData, AllScoreNormalDataValue, AllScoreTestData, AllScoreTestDataValue, AllScoreTestDataInfo, LackStatus, LackStatusValue}
+};
+use lopdf::{Document, Object};
+use ttf_parser::{Face, GlyphId};
+
+fn user() -> UserProfileShortValue {
+    UserProfileShortValue {
+        className: "101".to_owned(),
+        classNumber: "01".to_owned(),
+        gender: "M".to_owned(),
+        schoolNumber: "123456".to_owned(),
+        userName: "Wang".to_owned()
+    }
+}
+
+fn scores() -> AllScoreTestCollect {
+    let test = |test: u8, value: f32| AllScoreTestDataValue {
+        name: AllScoreTestDataInfo { name: format!("110-1-{}", test), year: 110, term: 1, test },
+        value
+    };
+
+    AllScoreTestCollect {
+        dataNormal: vec![AllScoreNormalData {
+            name: "English".to_owned(),
+            values: vec![AllScoreNormalDataValue { name: "110-1".to_owned(), value: 88.0 }]
+        }],
+        dataTest: vec![AllScoreTestData {
+            name: "Math".to_owned(),
+            values: vec![test(2, 45.0), test(1, 73.5)]
+        }]
+    }
+}
+
+fn lack() -> LackStatus {
+    LackStatus {
+        termUp: vec![LackStatusValue { name: "Sick".to_owned(), value: 3 }],
+        termDown: vec![LackStatusValue { name: "Sick".to_owned(), value: 0 }]
+    }
+}
+
+fn chinese_scores() -> AllScoreTestCollect {
+    let subject = |name: &str, value: f32| AllScoreNormalData {
+        name: name.to_owned(),
+        values: vec![AllScoreNormalDataValue { name: "一上".to_owned(), value }]
+    };
+
+    AllScoreTestCollect {
+        dataNormal: vec![subject("國文", 80.0), subject("數學", 72.5)],
+        dataTest: Vec::new()
+    }
+}
+
+// "<0001> <570B>" lines of the ToUnicode CMap of the regular font (`F0`) on the first page.
+fn to_unicode_map(document: &Document) -> HashMap<u16, char> {
+    let page = document.get_pages()[&1];
+    let fonts = document.get_page_fonts(page).unwrap();
+    let font = fonts[b"F0".as_slice()];
+    let to_unicode = document.get_object(font.get(b"ToUnicode").unwrap().as_reference().unwrap()).unwrap();
+    let cmap = to_unicode.as_stream().unwrap().decompressed_content().unwrap();
+
+    String::from_utf8(cmap)
+        .unwrap()
+        .lines()
+        .filter_map(|line| {
+            let (code, unicode) = line.strip_prefix('<')?.split_once("> <")?;
+            let unicode = u32::from_str_radix(unicode.strip_suffix('>')?, 16).ok()?;
+
+            Some((u16::from_str_radix(code, 16).ok()?, char::from_u32(unicode)?))
+        })
+        .collect()
+}
+
+#[test]
+fn transcript_text_is_selectable() {
+    let document = create_transcript_pages(&user(), None, &scores(), &lack(), "2024-01-02", "HLHSInfo");
+    let pdf = render_transcript(&document).unwrap();
+
+    let document = Document::load_mem(&pdf).unwrap();
+    let text = document.extract_text(&[1]).unwrap();
+
+    for expected in ["Wang", "123456", "2024-01-02", "English", "88", "110-1-1", "Math", "73.5", "45", "Sick", "HLHSInfo"] {
+        assert!(text.contains(expected), "{} is not in {:?}", expected, text);
+    }
+
+    // Exams are in the order of the school year.
+    assert!(text.find("110-1-1").unwrap() < text.find("110-1-2").unwrap());
+}
+
+// The codes of Identity-H are the CIDs of the bundled CID-keyed font, and ToUnicode maps them back to the text.
+#[test]
+fn chinese_text_is_mapped_to_its_cid() {
+    let document = create_transcript_pages(&user(), None, &chinese_scores(), &lack(), "2024-01-02", "HLHSInfo");
+    let document = Document::load_mem(&render_transcript(&document).unwrap()).unwrap();
+
+    let text = document.extract_text(&[1]).unwrap();
+    for expected in ["學生成績單", "國文", "數學", "72.5"] {
+        assert!(text.contains(expected), "{} is not in {:?}", expected, text);
+    }
+
+    let face = Face::parse(BUNDLED_FONTS[0], 0).unwrap();
+    let cff = face.tables().cff.unwrap();
+    let to_unicode = to_unicode_map(&document);
+
+    for c in "國文數學".chars() {
+        let glyph = face.glyph_index(c).unwrap_or(GlyphId(0));
+        let cid = cff.glyph_cid(glyph).unwrap_or(glyph.0);
+
+        assert_ne!(glyph.0, 0, "{} is not in the bundled font", c);
+        assert_eq!(to_unicode.get(&cid), Some(&c));
+    }
+}
+
+#[test]
+fn photo_is_written_once_for_every_page() {
+    let photo = render_svg_to_png(r##"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4"><rect width="4" height="4" fill="#336699"/></svg>"##).unwrap();
+    let mut scores = scores();
+    scores.dataTest = (1..=60).map(|i| AllScoreTestData { name: format!("Subject {}", i), values: scores.dataTest[0].values.clone() }).collect();
+
+    let document = create_transcript_pages(&user(), Some(&photo), &scores, &lack(), "2024-01-02", "HLHSInfo");
+    let document = Document::load_mem(&render_transcript(&document).unwrap()).unwrap();
+
+    let images = document.objects
+        .values()
+        .filter_map(|object| object.as_stream().ok())
+        .filter(|stream| stream.dict.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Image".as_slice()))
+        .count();
+    assert_eq!(images, 1);
+
+    let pages = document.get_pages();
+    assert!(pages.len() > 1);
+
+    let references = pages
+        .values()
+        .map(|page| {
+            let resources = document.get_dictionary(*page).unwrap().get(b"Resources").unwrap().as_dict().unwrap();
+            resources.get(b"XObject").unwrap().as_dict().unwrap().get(b"Im0").unwrap().as_reference().unwrap()
+        })
+        .collect::<std::collections::HashSet<_>>();
+    assert_eq!(references.len(), 1);
+}
+
+#[test]
+fn font_collection_is_rejected() {
+    let collection = [b"ttcf".as_slice(), &[0, 1, 0, 0], &[0, 0, 0, 1], &[0, 0, 0, 16]].concat();
+    let document = PdfDocument { pages: Vec::new(), images: Vec::new() };
+
+    assert!(matches!(create_pdf(&document, &PdfFont { data: &collection }, None), Err(PdfError::FontCollection)));
+}
@@ -0,0 +1,47 @@
+#!/bin/bash
+
+# Build the CJK font subset used to render timetable images and transcripts.
+# The subset keeps ASCII and the Big5 common characters (level 1), which covers course and student names.
+# Requires python3 with fonttools (pip install fonttools).
+
+FONT_URL="https://github.com/notofonts/noto-cjk/raw/main/Sans/SubsetOTF/TC"
+FONT_WEIGHTS=("Regular" "Bold")
+OUTPUT_PATH="$(dirname "$0")/../assets/fonts"
+WORK_PATH="$(mktemp -d)"
+
+if ! python3 -c "import fontTools" &>/dev/null; then
+    echo "fonttools not installed. Please run: pip install fonttools"
+    exit 1
+fi
+
+python3 - "$WORK_PATH/charset.txt" <<'PYTHON'
+import sys
+
+chars = [chr(c) for c in range(0x20, 0x7f)]
+chars += list("　，。、：；！？「」『』（）．‧－～")
+
+# Big5 level 1 common characters: 0xA440 - 0xC67E
+for high in range(0xa4, 0xc7):
+    for low in list(range(0x40, 0x7f)) + list(range(0xa1, 0xff)):
+        try:
+            chars.append(bytes([high, low]).decode("big5"))
+        except UnicodeDecodeError:
+            pass
+
+open(sys.argv[1], "w", encoding="utf-8").write("".join(chars))
+PYTHON
+
+mkdir -p "$OUTPUT_PATH"
+
+for weight in "${FONT_WEIGHTS[@]}"; do
+    curl -fsSL -o "$WORK_PATH/NotoSansTC-$weight.otf" "$FONT_URL/NotoSansTC-$weight.otf" || exit 1
+
+    python3 -m fontTools.subset "$WORK_PATH/NotoSansTC-$weight.otf" \
+        --text-file="$WORK_PATH/charset.txt" \
+        --layout-features="*" \
+        --output-file="$OUTPUT_PATH/NotoSansTC-$weight-Subset.otf" || exit 1
+
+    echo "Created $OUTPUT_PATH/NotoSansTC-$weight-Subset.otf"
+done
+
+rm -rf "$WORK_PATH"